@@ -0,0 +1,121 @@
+use {
+    alex::{read, with, Entity, Read, With, World, Write},
+    criterion::{black_box, criterion_group, criterion_main, Criterion},
+    std::{
+        future::Future,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll, Wake, Waker},
+    },
+};
+
+#[derive(Clone, Copy)]
+struct Pos([f32; 3]);
+
+#[derive(Clone, Copy)]
+struct Vel([f32; 3]);
+
+struct Tag<const N: usize>;
+
+/// Number of tag types.
+/// Each subset of tags gives unique archetype.
+const TAGS: usize = 9;
+
+fn insert_tag(world: &mut World, entity: Entity, tag: usize) {
+    macro_rules! insert {
+        ($($n:literal)*) => {
+            match tag {
+                $($n => drop(world.insert(entity, (Tag::<$n>,)).unwrap()),)*
+                _ => unreachable!(),
+            }
+        };
+    }
+    insert!(0 1 2 3 4 5 6 7 8);
+}
+
+/// Returns world with `2^TAGS` archetypes and few entities in each.
+fn populate() -> (World, Vec<Entity>) {
+    let mut world = World::new();
+    let mut entities = Vec::new();
+
+    for subset in 0..1usize << TAGS {
+        for _ in 0..4 {
+            let entity = world.spawn((Pos([0.0; 3]), Vel([1.0; 3])));
+            for tag in 0..TAGS {
+                if subset & (1 << tag) != 0 {
+                    insert_tag(&mut world, entity, tag);
+                }
+            }
+            entities.push(entity);
+        }
+    }
+
+    (world, entities)
+}
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn archetypes(c: &mut Criterion) {
+    let (mut world, entities) = populate();
+
+    c.bench_function("insert_remove", |b| {
+        let entity = entities[entities.len() / 2];
+        b.iter(|| {
+            world.insert(entity, (1u32,)).unwrap();
+            black_box(world.remove::<(u32,)>(entity).unwrap());
+        })
+    });
+
+    c.bench_function("spawn_despawn", |b| {
+        b.iter(|| {
+            let entity = world.spawn((Pos([0.0; 3]), Vel([1.0; 3]), Tag::<3>, Tag::<7>));
+            world.despawn(entity).unwrap();
+            world.maintain();
+        })
+    });
+
+    c.bench_function("query_sparse", |b| {
+        b.iter(|| {
+            world
+                .query::<(
+                    Write<Pos>,
+                    Read<Vel>,
+                    With<Tag<1>>,
+                    With<Tag<5>>,
+                    With<Tag<8>>,
+                )>()
+                .for_each(|(pos, vel, (), (), ())| pos.0[0] += vel.0[0])
+        })
+    });
+
+    c.bench_function("get_mut", |b| {
+        b.iter(|| {
+            for &entity in &entities {
+                black_box(world.get_mut::<Vel>(entity).unwrap());
+            }
+        })
+    });
+
+    c.bench_function("query_none", |b| {
+        b.iter(|| world.query::<(Read<Pos>, Read<u64>)>().count())
+    });
+
+    c.bench_function("lock_sparse", |b| {
+        let waker = Waker::from(Arc::new(Noop));
+        b.iter(|| {
+            let mut lock = world.lock((read::<Pos>(), with::<Tag<2>>(), with::<Tag<6>>()));
+            let mut ctx = Context::from_waker(&waker);
+            match Pin::new(&mut lock).poll(&mut ctx) {
+                Poll::Ready(access) => drop(black_box(access)),
+                Poll::Pending => unreachable!(),
+            }
+        })
+    });
+}
+
+criterion_group!(benches, archetypes);
+criterion_main!(benches);
@@ -1,24 +1,84 @@
 use {
     crate::component::ComponentInfo,
-    alloc::boxed::Box,
-    core::{alloc::Layout, any::TypeId, mem::size_of, ptr::NonNull},
+    alloc::{boxed::Box, vec::Vec},
+    core::{
+        alloc::Layout,
+        any::TypeId,
+        cmp::max,
+        mem::{align_of, size_of},
+    },
 };
 
 #[repr(transparent)]
 pub struct EntityIndex(pub usize);
 
 pub struct Component {
-    pub id: TypeId,
+    pub info: ComponentInfo,
     pub offset: usize,
-    pub size: usize,
-    pub name: &'static str,
-    pub drop_in_place: unsafe fn(NonNull<u8>),
+
+    /// Change ticks of the column.
+    /// Zero-sized tags have no column and no ticks.
+    pub ticks: Option<TickOffsets>,
+}
+
+impl Component {
+    pub fn id(&self) -> TypeId {
+        self.info.id()
+    }
+
+    pub fn size(&self) -> usize {
+        self.info.layout().size()
+    }
+
+    /// Returns `true` if component is zero-sized tag
+    /// that occupies no column memory.
+    pub fn is_tag(&self) -> bool {
+        self.size() == 0
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.info.name()
+    }
+}
+
+/// Offsets of change ticks of one component column in chunk.
+/// Ticks are laid out as `[chunk_changed, chunk_added, changed; capacity, added; capacity]`.
+/// Chunk ticks are maximums of row ticks of all entities ever placed in the chunk.
+#[derive(Clone, Copy)]
+pub struct TickOffsets {
+    offset: usize,
+    capacity: usize,
+}
+
+impl TickOffsets {
+    /// Number of ticks stored per chunk for a column with specified capacity.
+    fn count(capacity: usize) -> Option<usize> {
+        capacity.checked_mul(2)?.checked_add(2)
+    }
+
+    pub fn chunk_changed(&self) -> usize {
+        self.offset
+    }
+
+    pub fn chunk_added(&self) -> usize {
+        self.offset + size_of::<u64>()
+    }
+
+    /// Returns offset of the tick when component of entity at `index` was last borrowed mutably.
+    pub fn changed(&self, index: usize) -> usize {
+        debug_assert!(index < self.capacity);
+        self.offset + (2 + index) * size_of::<u64>()
+    }
+
+    /// Returns offset of the tick when component of entity at `index` was added.
+    pub fn added(&self, index: usize) -> usize {
+        debug_assert!(index < self.capacity);
+        self.offset + (2 + self.capacity + index) * size_of::<u64>()
+    }
 }
 
 pub struct Archetype {
     components: Box<[Component]>,
-    entity_align: usize,
-    entity_size: usize,
     chunk_capacity: usize,
     chunk_layout: Layout,
 }
@@ -32,61 +92,117 @@ use ArchetypeError::*;
 
 impl Archetype {
     /// Returns `Archetype` instance for specified components.
-    /// If chunk layout cannot be instantiated - returns `LayoutErr`.
-    pub fn new(mut components: Box<[ComponentInfo]>) -> Result<Self, ArchetypeError> {
-        components.iter().try_fold(0usize, |acc, c| {
-            acc.checked_add(c.layout().size()).ok_or(EntityIsTooLarge)
-        })?;
+    /// If chunk layout cannot be instantiated - returns `EntityIsTooLarge`.
+    pub fn new(components: Box<[ComponentInfo]>) -> Result<Self, ArchetypeError> {
+        Self::with_column_align(components.into_vec(), 1)
+    }
+
+    /// Returns `Archetype` instance for specified components.
+    /// Each component column in chunk is aligned to at least `column_align`.
+    /// Using cache line size prevents false sharing between columns.
+    /// If chunk layout cannot be instantiated - returns `EntityIsTooLarge`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `column_align` is not power of two.
+    pub fn with_column_align(
+        mut components: Vec<ComponentInfo>,
+        column_align: usize,
+    ) -> Result<Self, ArchetypeError> {
+        assert!(
+            column_align.is_power_of_two(),
+            "Column alignment must be power of two"
+        );
+
+        // Each entity occupies a row in every column and its change ticks.
+        let entity_size = components
+            .iter()
+            .filter(|c| c.layout().size() != 0)
+            .try_fold(size_of::<EntityIndex>(), |acc, c| {
+                acc.checked_add(c.layout().size())
+                    .and_then(|acc| acc.checked_add(2 * size_of::<u64>()))
+                    .ok_or(EntityIsTooLarge)
+            })?;
 
-        components.sort_unstable();
+        if entity_size > isize::MAX as usize {
+            return Err(EntityIsTooLarge);
+        }
 
         let entity_align = components
             .iter()
             .map(|c| c.layout().align())
-            .max()
-            .unwrap_or(1);
+            .fold(max(align_of::<EntityIndex>(), column_align), max)
+            .max(align_of::<u64>());
+
+        let chunk_capacity = chunk_capacity(entity_size);
+
+        // Columns are placed in order of alignment descended and then type id.
+        // This minimizes padding between columns.
+        components.sort_unstable_by(|lhs, rhs| {
+            Ord::cmp(&rhs.layout().align(), &lhs.layout().align())
+                .then_with(|| Ord::cmp(&lhs.id(), &rhs.id()))
+        });
 
-        let mut acc = size_of::<EntityIndex>();
+        // Column of entity indices is placed at the beginning of the chunk.
+        let mut acc = size_of::<EntityIndex>() * chunk_capacity;
 
         let mut components = components
             .iter()
             .map(|c| {
-                acc += c.layout().size();
-
-                Component {
-                    id: c.id(),
-                    offset: acc - c.layout().size(),
-                    size: c.layout().size(),
-                    name: c.name(),
-                    drop_in_place: c.drop_in_place(),
+                if c.layout().size() == 0 {
+                    // Zero-sized components occupy no column memory.
+                    // Chunk start is aligned for them.
+                    return Ok(Component {
+                        info: *c,
+                        offset: 0,
+                        ticks: None,
+                    });
                 }
-            })
-            .collect::<Box<[_]>>();
-
-        let entity_size = acc;
 
-        if entity_size > isize::MAX as usize {
-            return Err(EntityIsTooLarge);
+                let align = max(c.layout().align(), column_align);
+                let offset = align_up(acc, align).ok_or(EntityIsTooLarge)?;
+                let column_size = c
+                    .layout()
+                    .size()
+                    .checked_mul(chunk_capacity)
+                    .ok_or(EntityIsTooLarge)?;
+
+                acc = offset.checked_add(column_size).ok_or(EntityIsTooLarge)?;
+
+                Ok(Component {
+                    info: *c,
+                    offset,
+                    ticks: Some(TickOffsets {
+                        offset: 0,
+                        capacity: chunk_capacity,
+                    }),
+                })
+            })
+            .collect::<Result<Box<[_]>, _>>()?;
+
+        // Change ticks of all columns follow component columns.
+        // Ticks of each column are written only by holders of mutable access to the column,
+        // so they are aligned same way as columns.
+        let ticks_size = TickOffsets::count(chunk_capacity)
+            .and_then(|count| count.checked_mul(size_of::<u64>()))
+            .ok_or(EntityIsTooLarge)?;
+
+        for ticks in components.iter_mut().filter_map(|c| c.ticks.as_mut()) {
+            let align = max(align_of::<u64>(), column_align);
+            let offset = align_up(acc, align).ok_or(EntityIsTooLarge)?;
+            acc = offset.checked_add(ticks_size).ok_or(EntityIsTooLarge)?;
+            ticks.offset = offset;
         }
 
-        let chunk_capacity = chunk_capacity(entity_size, entity_align).ok_or(EntityIsTooLarge)?;
-
-        for c in &mut *components {
-            c.offset *= chunk_capacity;
-        }
+        // Components are looked up in order of type id.
+        components.sort_unstable_by_key(|c| c.id());
 
-        let chunk_layout = Layout::from_size_align(
-            chunk_capacity
-                .checked_mul(entity_size)
-                .ok_or(EntityIsTooLarge)?,
-            entity_align,
-        )
-        .map_err(|_| EntityIsTooLarge)?;
+        let chunk_layout = Layout::from_size_align(acc, entity_align)
+            .map_err(|_| EntityIsTooLarge)?
+            .pad_to_align();
 
         Ok(Archetype {
             components,
-            entity_size,
-            entity_align,
             chunk_capacity,
             chunk_layout,
         })
@@ -96,12 +212,23 @@ impl Archetype {
         &self.components
     }
 
+    /// Checks if archetype contains component with specified id.
+    pub fn contains(&self, id: TypeId) -> bool {
+        self.components
+            .binary_search_by_key(&id, Component::id)
+            .is_ok()
+    }
+
     pub fn ids(&self) -> impl Iterator<Item = TypeId> + Clone + '_ {
-        self.components.iter().map(|c| c.id)
+        self.components.iter().map(|c| c.id())
     }
 
     pub fn names(&self) -> impl Iterator<Item = &'static str> + Clone + '_ {
-        self.components.iter().map(|c| c.name)
+        self.components.iter().map(|c| c.name())
+    }
+
+    pub fn infos(&self) -> impl Iterator<Item = ComponentInfo> + Clone + '_ {
+        self.components.iter().map(|c| c.info)
     }
 
     pub fn chunk_capacity(&self) -> usize {
@@ -113,13 +240,15 @@ impl Archetype {
     }
 }
 
-fn chunk_capacity(entity_size: usize, entity_align: usize) -> Option<usize> {
-    debug_assert!(entity_align.is_power_of_two());
+/// Size of chunk that archetypes try to fit in.
+const CHUNK_SIZE: usize = 16384;
 
-    if entity_size == 0 {
-        Some(usize::MAX & !(entity_align))
-    } else {
-        const BASE: usize = 4095;
-        Some(((BASE / entity_size).checked_add(entity_align)?) & !(entity_align - 1))
-    }
+fn chunk_capacity(entity_size: usize) -> usize {
+    debug_assert_ne!(entity_size, 0);
+    max(1, CHUNK_SIZE / entity_size)
+}
+
+fn align_up(value: usize, align: usize) -> Option<usize> {
+    debug_assert!(align.is_power_of_two());
+    Some(value.checked_add(align - 1)? & !(align - 1))
 }
@@ -1,11 +1,12 @@
 use {
-    super::{Archetype, Component, EntityIndex},
+    super::{Archetype, Component, EntityIndex, TickOffsets},
     crate::{
-        bundle::Bundle,
-        util::{capacity_overflow, DisplayPunctuated as _},
+        bundle::{Bundle, StaticBundle},
+        component::{self, ComponentId},
+        util::{capacity_overflow, unreachable_unchecked, BitSet, DisplayPunctuated as _},
     },
     alloc::{
-        alloc::{alloc, handle_alloc_error},
+        alloc::{alloc, dealloc, handle_alloc_error},
         boxed::Box,
         vec::Vec,
     },
@@ -13,7 +14,7 @@ use {
         any::{type_name, TypeId},
         cell::Cell,
         mem::{forget, size_of},
-        ptr::{write, NonNull},
+        ptr::{copy_nonoverlapping, read, swap_nonoverlapping, write, NonNull},
     },
 };
 
@@ -21,6 +22,7 @@ use {
 struct Place {
     ptr: NonNull<u8>,
     init: bool,
+    expected: bool,
 }
 
 impl Place {
@@ -28,23 +30,55 @@ impl Place {
         Place {
             ptr: NonNull::dangling(),
             init: false,
+            expected: false,
         }
     }
 }
 pub struct ArchetypeStorage {
     archetype: Archetype,
+
+    /// Set of ids of archetype components.
+    signature: BitSet,
+
+    /// Column indices ordered by component id,
+    /// so column of a component is found by its rank in the signature.
+    columns: Box<[usize]>,
+
+    /// Component ids ordered by column.
+    ids: Box<[ComponentId]>,
+
     chunks: Vec<NonNull<u8>>,
     len: usize,
     places_cache: Box<[Place]>,
 }
 
+/// Storage owns components in its chunks.
+/// Only `Send + Sync` components can be initialized in storage.
+/// Shared access to components is controlled by archetype locks.
+unsafe impl Send for ArchetypeStorage {}
+unsafe impl Sync for ArchetypeStorage {}
+
 impl ArchetypeStorage {
     /// Returns storage for specified archetype.
-    pub fn new(archetype: Archetype) -> Self {
+    /// `ids` are ids of archetype components in the same order.
+    pub fn new(archetype: Archetype, ids: Box<[ComponentId]>) -> Self {
+        debug_assert_eq!(archetype.components().len(), ids.len());
+
+        let mut signature = BitSet::new();
+        for id in ids.iter() {
+            signature.insert(id.index());
+        }
+
+        let mut columns: Box<[usize]> = (0..ids.len()).collect();
+        columns.sort_unstable_by_key(|&c| ids[c]);
+
         ArchetypeStorage {
             places_cache: alloc::vec![Place::new(); archetype.components().len()]
                 .into_boxed_slice(), //TODO: switch to `Box::new_zeroed_slice()` when stable
             archetype,
+            signature,
+            columns,
+            ids,
             chunks: Vec::new(),
             len: 0,
         }
@@ -54,6 +88,11 @@ impl ArchetypeStorage {
         &self.archetype
     }
 
+    /// Returns set of ids of archetype components.
+    pub fn signature(&self) -> &BitSet {
+        &self.signature
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -66,14 +105,46 @@ impl ArchetypeStorage {
         self.chunks.len() * self.archetype.chunk_capacity()
     }
 
-    pub fn insert<B>(&mut self, bundle: B, entity: usize) -> usize
+    /// Inserts new entity initializing components from the bundle.
+    /// Components are marked as added and changed at `tick`.
+    /// Returns index of the entity.
+    pub fn insert<B>(&mut self, bundle: B, entity: usize, tick: u64) -> usize
     where
         B: Bundle + 'static,
     {
         #[cfg(debug_assertions)]
         {
             // Validate that correct archetype is chosen.
-            if bundle.with_ids(|ids| ids.iter().copied().eq(self.archetype.ids())) {
+            if !bundle.with_ids(|ids| ids.iter().copied().eq(self.archetype.ids())) {
+                bundle.with_type_names(|names| {
+                    panic!("Incorrect `Archetype` for `Bundle`.\n  Archetype components: [{}]\n  Souce components: [{}]", self.archetype.names().display_punctuated(), names.display_punctuated())
+                })
+            }
+        }
+
+        unsafe {
+            // SAFETY: Bundle contains all components of the archetype.
+            self.insert_partial(bundle, entity, tick)
+        }
+    }
+
+    /// Inserts new entity initializing components from the bundle.
+    /// Bundle may contain only subset of archetype's components.
+    /// Components from the bundle are marked as added and changed at `tick`.
+    /// Returns index of the entity.
+    ///
+    /// # Safety
+    ///
+    /// Caller must initialize all components that are absent in the bundle
+    /// before any other operation with this storage.
+    pub unsafe fn insert_partial<B>(&mut self, bundle: B, entity: usize, tick: u64) -> usize
+    where
+        B: Bundle + 'static,
+    {
+        #[cfg(debug_assertions)]
+        {
+            // Validate that archetype contains all bundle's components.
+            if !bundle.with_ids(|ids| ids.iter().all(|id| self.archetype.ids().any(|a| a == *id))) {
                 bundle.with_type_names(|names| {
                     panic!("Incorrect `Archetype` for `Bundle`.\n  Archetype components: [{}]\n  Souce components: [{}]", self.archetype.names().display_punctuated(), names.display_punctuated())
                 })
@@ -86,26 +157,111 @@ impl ArchetypeStorage {
         }
         debug_assert!(self.capacity() > self.len);
 
-        let chunk = self.len / self.archetype.chunk_capacity();
-        let index = self.len % self.archetype.chunk_capacity();
+        let index = self.len;
+        if !self.init_components(bundle, index) {
+            panic!(
+                "Not all components were initialized by `<{} as Bundle>::init_components`",
+                type_name::<B>(),
+            )
+        }
+
+        let chunk = index / self.archetype.chunk_capacity();
+        let chunk_index = index % self.archetype.chunk_capacity();
 
         let chunk_ptr = self.chunks[chunk];
 
-        for (p, c) in Iterator::zip(
-            self.places_cache.iter_mut(),
-            self.archetype.components().iter(),
-        ) {
-            let offset = c.offset + index * c.size;
-            debug_assert!(offset <= self.archetype.chunk_layout().size());
-
-            p.ptr = unsafe {
-                // SAFETY: `chunk_ptr` points to the begining of chunk with layout `self.archetype.chunk_layout`.
-                // Check above guarentees that offset is not out of bound of allocation, so adding it may not overflow pointer value.
-                // `offset` may overflow only due to bug in this `Archetype` or this module.
-                NonNull::new_unchecked(chunk_ptr.as_ptr().add(offset))
-            };
+        let offset = chunk_index * size_of::<EntityIndex>();
+        debug_assert!(offset <= self.archetype.chunk_layout().size());
+
+        // SAFETY: `chunk_ptr` points to the begining of chunk with layout `self.archetype.chunk_layout`.
+        // Check above guarentees that offset is not out of bound of allocation, so adding it may not overflow pointer value.
+        // `offset` may overflow only due to bug in this `Archetype` or this module.
+        let ptr = chunk_ptr.as_ptr().add(offset);
+
+        write(ptr as *mut _, EntityIndex(entity));
+
+        for (c, p) in self.places_cache.iter().enumerate() {
+            if p.expected {
+                self.set_ticks_unchecked(c, index, tick, tick);
+            }
+        }
+
+        self.len += 1;
+        index
+    }
+
+    /// Replaces components of the entity at `index` with components from the bundle.
+    /// Replaced components are marked as changed at `tick`, ticks when they were added are kept.
+    /// Returns old components.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.len()`.
+    /// Archetype must contain all components of the bundle.
+    pub unsafe fn replace<B>(&mut self, bundle: B, index: usize, tick: u64) -> B::Optional
+    where
+        B: StaticBundle + 'static,
+    {
+        debug_assert!(index < self.len);
+
+        debug_assert!(self.capacity() >= self.len);
+        if self.capacity() == self.len {
+            self.alloc_chunk();
+        }
+        debug_assert!(self.capacity() > self.len);
+
+        // New components are initialized in the spare row first,
+        // so that failed initialization leaves the entity intact.
+        let spare = self.len;
+        if !self.init_components(bundle, spare) {
+            panic!(
+                "Not all components were initialized by `<{} as Bundle>::init_components`",
+                type_name::<B>(),
+            )
+        }
+
+        let replaced = self.take_optional_components::<B>(index);
+
+        for (c, component) in self.archetype.components().iter().enumerate() {
+            if self.places_cache[c].expected {
+                copy_nonoverlapping(
+                    self.component_ptr_unchecked(c, spare).as_ptr(),
+                    self.component_ptr_unchecked(c, index).as_ptr(),
+                    component.size(),
+                );
+
+                if let Some((_, added)) = self.ticks_unchecked(c, index) {
+                    self.set_ticks_unchecked(c, index, tick, added);
+                }
+            }
         }
 
+        replaced
+    }
+
+    /// Initializes components from the bundle for the entity at `index`.
+    /// Returns `false` if bundle did not initialize all its components,
+    /// components initialized so far are dropped.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.capacity()`.
+    /// Archetype must contain all components of the bundle
+    /// and they must be uninitialized at `index`.
+    unsafe fn init_components<B>(&mut self, bundle: B, index: usize) -> bool
+    where
+        B: Bundle,
+    {
+        bundle.with_ids(|ids| {
+            for (c, component) in self.archetype.components().iter().enumerate() {
+                let ptr = self.component_ptr_unchecked(c, index);
+                let p = self.places_cache.get_unchecked_mut(c);
+                p.ptr = ptr;
+                p.init = false;
+                p.expected = ids.contains(&component.id());
+            }
+        });
+
         // Prepare to share `places`.
         let places = Cell::from_mut(&mut *self.places_cache).as_slice_of_cells();
 
@@ -121,48 +277,267 @@ impl ArchetypeStorage {
 
         bundle.init_components(uninit);
 
-        if places.iter().all(|p| p.get().init) {
+        if places.iter().all(|p| p.get().init || !p.get().expected) {
             // All components are initialized.
-            let offset = index * size_of::<EntityIndex>();
-            debug_assert!(offset <= self.archetype.chunk_layout().size());
+            forget(drop_initialized);
+            true
+        } else {
+            false
+        }
+    }
 
-            let ptr = unsafe {
-                // SAFETY: `chunk_ptr` points to the begining of chunk with layout `self.archetype.chunk_layout`.
-                // Check above guarentees that offset is not out of bound of allocation, so adding it may not overflow pointer value.
-                // `offset` may overflow only due to bug in this `Archetype` or this module.
-                chunk_ptr.as_ptr().add(offset)
-            };
+    /// Removes all entities from storage dropping their components.
+    /// Allocated chunks are kept for reuse.
+    pub fn clear(&mut self) {
+        while self.len > 0 {
+            self.len -= 1;
 
-            unsafe { write(ptr as *mut _, EntityIndex(entity)) }
+            unsafe {
+                // SAFETY: Entity at `self.len` was initialized
+                // and is no longer accessible.
+                self.drop_row(self.len);
+            }
+        }
+    }
 
-            forget(drop_initialized);
-            self.len += 1;
-            self.len - 1
+    /// Removes entity from storage dropping its components.
+    /// Last entity is moved into freed place.
+    /// Returns index of the moved entity if any.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Option<usize> {
+        assert!(index < self.len, "Entity index is out of bounds");
+
+        unsafe {
+            // SAFETY: `index` is in bounds.
+            // Components of removed entity are dropped below.
+            let moved = self.swap_remove(index);
+            self.drop_row(self.len);
+            moved
+        }
+    }
+
+    /// Removes entity from storage without dropping its components.
+    /// Last entity is moved into freed place.
+    /// Returns index of the moved entity if any.
+    ///
+    /// Components of the removed entity are swapped into place at `self.len()`
+    /// and stay there until next insertion, so caller can move or drop them.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.len()`.
+    /// Caller is responsible to move or drop components of the removed entity.
+    pub unsafe fn swap_remove(&mut self, index: usize) -> Option<usize> {
+        debug_assert!(index < self.len);
+        let last = self.len - 1;
+        self.len = last;
+
+        if index == last {
+            None
         } else {
-            // Drop initialized components and panic.
-            drop(drop_initialized);
-            panic!(
-                "Not all components were initialized by `<{} as Bundle>::init_components`",
-                type_name::<B>(),
-            )
+            for (c, component) in self.archetype.components().iter().enumerate() {
+                if component.is_tag() {
+                    continue;
+                }
+
+                // Ticks of removed entity are not needed.
+                if let Some((changed, added)) = self.ticks_unchecked(c, last) {
+                    self.set_ticks_unchecked(c, index, changed, added);
+                }
+
+                swap_nonoverlapping(
+                    self.component_ptr_unchecked(c, index).as_ptr(),
+                    self.component_ptr_unchecked(c, last).as_ptr(),
+                    component.size(),
+                );
+            }
+
+            let moved = read(self.entity_index_ptr_unchecked(last).as_ptr());
+            write(
+                self.entity_index_ptr_unchecked(index).as_ptr(),
+                EntityIndex(moved.0),
+            );
+            Some(moved.0)
         }
     }
 
-    pub fn get_component_ref<T: 'static>(&self, index: usize) -> Option<&T> {
-        let ptr = self.get_component_ptr(index)?;
-        Some(unsafe { &*ptr.as_ptr() })
+    /// Moves components absent in `ids` from entity at `index` in this storage
+    /// to entity at `dst_index` in `dst` storage along with their change ticks.
+    /// Components in `ids` that `dst` also contains keep tick when they were added.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.len()` and `dst_index` less than `dst.len()`.
+    /// `dst` archetype must contain all components absent in `ids`.
+    /// Moved components in this storage become uninitialized,
+    /// while components in `dst` storage must be uninitialized before the call.
+    pub unsafe fn move_components_except(
+        &self,
+        ids: &[TypeId],
+        index: usize,
+        dst: &ArchetypeStorage,
+        dst_index: usize,
+    ) {
+        for (c, component) in self.archetype.components().iter().enumerate() {
+            if component.is_tag() {
+                // Tags have neither column nor ticks.
+                continue;
+            }
+
+            let (changed, added) = self
+                .ticks_unchecked(c, index)
+                .unwrap_or_else(|| unreachable_unchecked());
+
+            if ids.contains(&component.id()) {
+                // Component is replaced or removed.
+                if let Some(dst_c) = dst.component_index(self.ids[c]) {
+                    if let Some((changed, _)) = dst.ticks_unchecked(dst_c, dst_index) {
+                        dst.set_ticks_unchecked(dst_c, dst_index, changed, added);
+                    }
+                }
+                continue;
+            }
+
+            let dst_c = dst.component_index(self.ids[c]);
+            debug_assert!(dst_c.is_some());
+            let dst_c = dst_c.unwrap_or_else(|| unreachable_unchecked());
+
+            dst.set_ticks_unchecked(dst_c, dst_index, changed, added);
+
+            copy_nonoverlapping(
+                self.component_ptr_unchecked(c, index).as_ptr(),
+                dst.component_ptr_unchecked(dst_c, dst_index).as_ptr(),
+                component.size(),
+            );
+        }
     }
 
-    pub fn get_component_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
-        let ptr = self.get_component_ptr(index)?;
-        Some(unsafe { &mut *ptr.as_ptr() })
+    /// Drops components with specified ids of the entity at `index`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.capacity()`.
+    /// Components must be initialized and are considered uninitialized after the call.
+    pub unsafe fn drop_components(&mut self, ids: &[TypeId], index: usize) {
+        for (c, component) in self.archetype.components().iter().enumerate() {
+            if ids.contains(&component.id()) {
+                (component.info.drop_in_place())(self.component_ptr_unchecked(c, index));
+            }
+        }
     }
 
-    pub fn component_index(&self, id: TypeId) -> Option<usize> {
-        self.archetype
-            .components()
+    /// Drops all components of the entity at `index`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.capacity()`.
+    /// Components must be initialized and are considered uninitialized after the call.
+    unsafe fn drop_row(&mut self, index: usize) {
+        for (c, component) in self.archetype.components().iter().enumerate() {
+            (component.info.drop_in_place())(self.component_ptr_unchecked(c, index));
+        }
+    }
+
+    /// Takes components of the bundle from the entity at `index`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.capacity()`.
+    /// All components of the bundle must be initialized and are considered uninitialized after the call.
+    pub unsafe fn take_components<B>(&mut self, index: usize) -> B
+    where
+        B: StaticBundle + 'static,
+    {
+        debug_assert!(B::static_with_ids(|ids| ids
             .iter()
-            .position(move |c| c.id == id)
+            .all(|id| self.archetype.ids().any(|a| a == *id))));
+
+        self.take_with::<B, _>(index, B::take_components)
+    }
+
+    /// Takes components of the bundle that archetype contains from the entity at `index`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.capacity()`.
+    /// Those components must be initialized and are considered uninitialized after the call.
+    pub unsafe fn take_optional_components<B>(&mut self, index: usize) -> B::Optional
+    where
+        B: StaticBundle + 'static,
+    {
+        self.take_with::<B, _>(index, B::take_optional)
+    }
+
+    /// Calls closure with components of the bundle that archetype contains
+    /// of the entity at `index`.
+    /// Components left by the closure are dropped.
+    unsafe fn take_with<B, R>(&mut self, index: usize, f: impl FnOnce(TakeComponents<'_>) -> R) -> R
+    where
+        B: StaticBundle,
+    {
+        B::static_with_ids(|ids| {
+            for (c, component) in self.archetype.components().iter().enumerate() {
+                let ptr = self.component_ptr_unchecked(c, index);
+                let p = self.places_cache.get_unchecked_mut(c);
+                p.ptr = ptr;
+                p.expected = ids.contains(&component.id());
+                p.init = p.expected;
+            }
+        });
+
+        // Prepare to share `places`.
+        let places = Cell::from_mut(&mut *self.places_cache).as_slice_of_cells();
+
+        let take = TakeComponents {
+            components: self.archetype.components(),
+            places,
+        };
+
+        // Components left by `f` are dropped.
+        let _drop_initialized = DropInitialized {
+            components: self.archetype.components(),
+            places,
+        };
+
+        f(take)
+    }
+
+    /// Returns component of the entity at `index`.
+    /// `id` must be id of component type `T`.
+    pub fn get_component_ref<T: 'static>(&self, id: ComponentId, index: usize) -> Option<&T> {
+        let c = self.component_index(id)?;
+        let ptr = self.get_component_ptr::<T>(c, index)?;
+        Some(unsafe { &*ptr.as_ptr() })
+    }
+
+    /// Returns component of the entity at `index` marking it changed at `tick`.
+    /// `id` must be id of component type `T`.
+    pub fn get_component_mut<T: 'static>(
+        &mut self,
+        id: ComponentId,
+        index: usize,
+        tick: u64,
+    ) -> Option<&mut T> {
+        let c = self.component_index(id)?;
+        let ptr = self.get_component_ptr::<T>(c, index)?;
+
+        unsafe {
+            // SAFETY: `get_component_ptr` checked that entity exists.
+            if let Some((_, added)) = self.ticks_unchecked(c, index) {
+                self.set_ticks_unchecked(c, index, tick, added);
+            }
+
+            Some(&mut *ptr.as_ptr())
+        }
+    }
+
+    /// Returns index of the component column.
+    pub fn component_index(&self, id: ComponentId) -> Option<usize> {
+        let rank = self.signature.rank(id.index())?;
+        Some(self.columns[rank])
     }
 
     pub unsafe fn component_offset_by_index_unchecked(&self, index: usize) -> usize {
@@ -170,6 +545,19 @@ impl ArchetypeStorage {
         self.archetype.components().get_unchecked(index).offset
     }
 
+    pub unsafe fn component_ticks_by_index_unchecked(&self, index: usize) -> Option<TickOffsets> {
+        debug_assert!(self.archetype.components().len() > index);
+        self.archetype.components().get_unchecked(index).ticks
+    }
+
+    /// Returns indices of all entities in the storage.
+    pub fn entity_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).map(move |index| unsafe {
+            // SAFETY: `index` is less than `self.len`.
+            read(self.entity_index_ptr_unchecked(index).as_ptr()).0
+        })
+    }
+
     pub fn raw_chunks(&self) -> &[NonNull<u8>] {
         &self.chunks
     }
@@ -177,7 +565,7 @@ impl ArchetypeStorage {
     #[cfg(debug_assertions)]
     pub fn is_correct_index_offset(&self, id: TypeId, index: usize, offset: usize) -> bool {
         let component = &self.archetype.components()[index];
-        component.id == id && component.offset == offset
+        component.id() == id && component.offset == offset
     }
 
     fn alloc_chunk(&mut self) {
@@ -211,28 +599,148 @@ impl ArchetypeStorage {
         let ptr =
             NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(self.archetype.chunk_layout()));
 
+        for ticks in self.archetype.components().iter().filter_map(|c| c.ticks) {
+            unsafe {
+                // SAFETY: Chunk ticks are within chunk layout.
+                write(ptr.as_ptr().add(ticks.chunk_changed()) as *mut u64, 0);
+                write(ptr.as_ptr().add(ticks.chunk_added()) as *mut u64, 0);
+            }
+        }
+
         self.chunks.push(ptr);
     }
 
-    fn get_component_ptr<T: 'static>(&self, index: usize) -> Option<NonNull<T>> {
-        let id = TypeId::of::<T>();
-        self.get_component_ptr_erased(id, index)
-            .map(NonNull::cast::<T>)
+    fn get_component_ptr<T: 'static>(&self, component: usize, index: usize) -> Option<NonNull<T>> {
+        debug_assert_eq!(
+            self.archetype.components()[component].id(),
+            TypeId::of::<T>()
+        );
+
+        if index >= self.len {
+            return None;
+        }
+
+        Some(unsafe {
+            // SAFETY: `component` is valid index and `index` is less than `self.len`.
+            self.component_ptr_unchecked(component, index).cast()
+        })
     }
 
-    fn get_component_ptr_erased(&self, id: TypeId, index: usize) -> Option<NonNull<u8>> {
-        let component = self.component_index(id)?;
-        let component = &self.archetype.components()[component];
+    /// Returns pointer to the component of the entity at `index`.
+    ///
+    /// # Safety
+    ///
+    /// `component` must be valid component index and `index` must be less than `self.capacity()`.
+    unsafe fn component_ptr_unchecked(&self, component: usize, index: usize) -> NonNull<u8> {
+        debug_assert!(component < self.archetype.components().len());
+        debug_assert!(index < self.capacity());
+
+        let component = self.archetype.components().get_unchecked(component);
 
         let chunk = index / self.archetype.chunk_capacity();
         let index = index % self.archetype.chunk_capacity();
 
-        let offset = component.offset + index * component.size;
+        let offset = component.offset + index * component.size();
         debug_assert!(offset <= self.archetype.chunk_layout().size());
 
-        let chunk_ptr = self.chunks.get(chunk)?;
+        let chunk_ptr = self.chunks.get_unchecked(chunk);
+        NonNull::new_unchecked(chunk_ptr.as_ptr().add(offset))
+    }
 
-        Some(unsafe { NonNull::new_unchecked(chunk_ptr.as_ptr().add(offset)) })
+    /// Returns changed and added ticks of component of the entity at `index`.
+    /// Returns `None` for tags.
+    ///
+    /// # Safety
+    ///
+    /// `component` must be valid component index and `index` must be less than `self.capacity()`.
+    /// Ticks must be initialized.
+    unsafe fn ticks_unchecked(&self, component: usize, index: usize) -> Option<(u64, u64)> {
+        let (chunk_ptr, ticks, index) = self.ticks_location_unchecked(component, index)?;
+        Some((
+            read(chunk_ptr.add(ticks.changed(index)) as *const u64),
+            read(chunk_ptr.add(ticks.added(index)) as *const u64),
+        ))
+    }
+
+    /// Sets changed and added ticks of component of the entity at `index`.
+    /// Ticks of the chunk are raised accordingly.
+    /// Does nothing for tags.
+    ///
+    /// # Safety
+    ///
+    /// `component` must be valid component index and `index` must be less than `self.capacity()`.
+    /// Caller must have exclusive access to the component.
+    unsafe fn set_ticks_unchecked(&self, component: usize, index: usize, changed: u64, added: u64) {
+        let (chunk_ptr, ticks, index) = match self.ticks_location_unchecked(component, index) {
+            Some(location) => location,
+            None => return,
+        };
+
+        write(chunk_ptr.add(ticks.changed(index)) as *mut u64, changed);
+        write(chunk_ptr.add(ticks.added(index)) as *mut u64, added);
+
+        let chunk_changed = chunk_ptr.add(ticks.chunk_changed()) as *mut u64;
+        let chunk_added = chunk_ptr.add(ticks.chunk_added()) as *mut u64;
+        if *chunk_changed < changed {
+            *chunk_changed = changed;
+        }
+        if *chunk_added < added {
+            *chunk_added = added;
+        }
+    }
+
+    /// Returns pointer to the chunk with the entity at `index`,
+    /// ticks offsets of the component and index of the entity in the chunk.
+    /// Returns `None` for tags.
+    ///
+    /// # Safety
+    ///
+    /// `component` must be valid component index and `index` must be less than `self.capacity()`.
+    unsafe fn ticks_location_unchecked(
+        &self,
+        component: usize,
+        index: usize,
+    ) -> Option<(*mut u8, TickOffsets, usize)> {
+        debug_assert!(component < self.archetype.components().len());
+        debug_assert!(index < self.capacity());
+
+        let ticks = self.archetype.components().get_unchecked(component).ticks?;
+
+        let chunk = index / self.archetype.chunk_capacity();
+        let index = index % self.archetype.chunk_capacity();
+
+        Some((self.chunks.get_unchecked(chunk).as_ptr(), ticks, index))
+    }
+
+    /// Returns pointer to the entity index of the entity at `index`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.capacity()`.
+    unsafe fn entity_index_ptr_unchecked(&self, index: usize) -> NonNull<EntityIndex> {
+        debug_assert!(index < self.capacity());
+
+        let chunk = index / self.archetype.chunk_capacity();
+        let index = index % self.archetype.chunk_capacity();
+
+        let offset = index * size_of::<EntityIndex>();
+        debug_assert!(offset <= self.archetype.chunk_layout().size());
+
+        let chunk_ptr = self.chunks.get_unchecked(chunk);
+        NonNull::new_unchecked(chunk_ptr.as_ptr().add(offset) as *mut EntityIndex)
+    }
+}
+
+impl Drop for ArchetypeStorage {
+    fn drop(&mut self) {
+        self.clear();
+
+        for &chunk in &self.chunks {
+            unsafe {
+                // SAFETY: Chunks are allocated in `alloc_chunk` with this layout.
+                dealloc(chunk.as_ptr(), self.archetype.chunk_layout())
+            }
+        }
     }
 }
 
@@ -260,7 +768,7 @@ impl Drop for DropInitialized<'_> {
         for (p, c) in Iterator::zip(self.places.iter(), self.components.iter()) {
             let p = p.get();
             if p.init {
-                unsafe { (c.drop_in_place)(p.ptr) }
+                unsafe { (c.info.drop_in_place())(p.ptr) }
             }
         }
     }
@@ -274,16 +782,12 @@ impl UninitComponents<'_> {
     ///
     /// This instance must expect component of type `T`.
     /// Component of type `T` must not have been yet initialized.
-    pub fn init_some<T: 'static>(&mut self, value: T) {
-        let pos = match self
-            .components
-            .iter()
-            .position(|c| c.id == TypeId::of::<T>())
-        {
+    pub fn init_some<T: component::Component>(&mut self, value: T) {
+        let pos = match find_expected::<T>(self.components, self.places) {
             None => panic!(
                 "Failed to insert component of type `{}`. Expected one of `{}`",
                 type_name::<T>(),
-                self.components.iter().map(|c| c.name).display_punctuated(),
+                expected_names(self.components, self.places).display_punctuated(),
             ),
             Some(pos) => pos,
         };
@@ -302,3 +806,82 @@ impl UninitComponents<'_> {
         }
     }
 }
+
+/// Contains pointers to components being taken out of storage.
+/// User should call `TakeComponents::take_some` or `TakeComponents::take_optional` function
+/// to take components with correct type in arbitrary order.
+///
+/// `StaticBundle` implementation receive instance of this type.
+/// Components left in `TakeComponents` are dropped.
+pub struct TakeComponents<'a> {
+    components: &'a [Component],
+    places: &'a [Cell<Place>],
+}
+
+impl TakeComponents<'static> {
+    /// Returns instance that contains no components.
+    pub(crate) fn empty() -> Self {
+        TakeComponents {
+            components: &[],
+            places: &[],
+        }
+    }
+}
+
+impl TakeComponents<'_> {
+    /// Take one of the component.
+    /// Receiver of `TakeComponents` instance should call this function for all components.
+    ///
+    /// # Panics
+    ///
+    /// This instance must contain component of type `T`.
+    /// Component of type `T` must not have been yet taken.
+    pub fn take_some<T: 'static>(&mut self) -> T {
+        let pos = match find_expected::<T>(self.components, self.places) {
+            None => panic!(
+                "Failed to take component of type `{}`. Expected one of `{}`",
+                type_name::<T>(),
+                expected_names(self.components, self.places).display_punctuated(),
+            ),
+            Some(pos) => pos,
+        };
+
+        let mut place = self.places[pos].get();
+
+        if place.init {
+            let value = unsafe { read(place.ptr.as_ptr() as *mut T) };
+            place.init = false;
+            self.places[pos].set(place);
+            value
+        } else {
+            panic!(
+                "Failed to take component of type `{}` twice",
+                type_name::<T>()
+            )
+        }
+    }
+
+    /// Take one of the components if this instance contains it.
+    ///
+    /// # Panics
+    ///
+    /// Component of type `T` must not have been yet taken.
+    pub fn take_optional<T: 'static>(&mut self) -> Option<T> {
+        find_expected::<T>(self.components, self.places)?;
+        Some(self.take_some())
+    }
+}
+
+fn find_expected<T: 'static>(components: &[Component], places: &[Cell<Place>]) -> Option<usize> {
+    Iterator::zip(components.iter(), places.iter())
+        .position(|(c, p)| c.id() == TypeId::of::<T>() && p.get().expected)
+}
+
+fn expected_names<'a>(
+    components: &'a [Component],
+    places: &'a [Cell<Place>],
+) -> impl Iterator<Item = &'static str> + Clone + 'a {
+    Iterator::zip(components.iter(), places.iter())
+        .filter(|(_, p)| p.get().expected)
+        .map(|(c, _)| c.name())
+}
@@ -1,260 +1,613 @@
 use {
     crate::{
-        archetype::{Archetype, ArchetypeError, ArchetypeStorage},
-        bundle::Bundle,
-        component::ComponentInfo,
-        entity::{Entity, EntityLocations, Location},
+        archetype::ArchetypeStorage,
+        component::ComponentRegistry,
+        entity::{Entity, EntityLocations},
         query::{
-            iter::ArchetypeEntityIter, Access, AccessComponent, AccessKind, ArchetypeAccess, View,
+            archetype_matches,
+            iter::{fetch_one, matches_one, query_archetype_chunks, query_archetypes},
+            Access, AccessKind, ArchetypeAccess, View,
         },
-        util::{unreachable_unchecked, MutableGuard, SharedGuard, TypeIdListMap, TypeIdMap},
-        world::World,
+        resource::Resource,
+        util::{AsyncLock, MutableGuard, SharedGuard},
+        world::{GetError, World},
     },
-    alloc::{boxed::Box, vec::Vec},
+    alloc::vec::Vec,
     core::{
         any::TypeId,
         cell::Cell,
-        cmp::{Ord, Ordering},
         future::Future,
-        mem::replace,
         pin::Pin,
-        task::{Context, Poll, Waker},
+        task::{Context, Poll},
     },
-    hashbrown::hash_map::RawEntryMut,
-    spin::Mutex,
 };
 
+#[cfg(feature = "parallel")]
+use {
+    crate::query::par::{par_for_each_archetypes, par_query_archetype_chunks},
+    rayon::iter::IndexedParallelIterator,
+};
+
+/// Guards are held only to release locks on drop.
+#[allow(dead_code)]
 enum Guard<'a> {
     Shared(SharedGuard<'a>),
     Mutable(MutableGuard<'a>),
 }
 
 pub struct AsyncArchetypeAccess<'a> {
-    guards: Vec<Guard<'a>>,
+    /// Index of the archetype in the world.
+    index: usize,
+    _guards: Vec<Guard<'a>>,
     granted: Vec<Cell<usize>>,
     storage: &'a ArchetypeStorage,
+    registry: &'a ComponentRegistry,
+    entities: &'a EntityLocations,
+    tick: u64,
 }
 
-impl AsyncArchetypeAccess<'_> {
-    pub fn get(&mut self) -> ArchetypeAccess<'_> {
-        ArchetypeAccess::new(&mut self.granted, self.storage)
+impl<'a> AsyncArchetypeAccess<'a> {
+    pub fn get(&self) -> ArchetypeAccess<'_> {
+        ArchetypeAccess::new(
+            &self.granted,
+            self.storage,
+            self.registry,
+            self.entities,
+            self.tick,
+        )
     }
 }
 
+struct AsyncResourceAccess<'a> {
+    id: TypeId,
+    _guard: Guard<'a>,
+    kind: AccessKind,
+    resource: &'a Resource,
+}
+
+/// Accesses to components and resources of the `World` granted by `World::lock`.
+/// Locks are held until this value is dropped.
 pub struct AsyncWorldAccess<'a> {
+    world: &'a World,
+
+    /// Sorted by archetype index.
     archetypes: Vec<AsyncArchetypeAccess<'a>>,
+
+    /// Sorted by resource id.
+    resources: Vec<AsyncResourceAccess<'a>>,
 }
 
 impl<'a> AsyncWorldAccess<'a> {
-    pub fn iter_view<V: View<'a> + 'a>(
-        &'a mut self,
-        view: &'a V,
-    ) -> impl Iterator<Item = <V as View<'a>>::EntityView> + 'a {
-        self.archetypes.iter_mut().flat_map(move |archetype| {
-            ArchetypeEntityIter {
-                raw_chunks: archetype.storage.raw_chunks().iter(),
-                len: archetype.storage.len(),
-                chunk_capacity: archetype.storage.chunk_capacity(),
-                refs: view.acquire(archetype.get()),
+    /// Returns resource of type `T`.
+    /// Returns `None` if resource is missing or was not locked.
+    pub fn resource<T: 'static>(&self) -> Option<&T> {
+        let resource = self.find_resource(TypeId::of::<T>())?;
+        // SAFETY: Resource is locked by the access.
+        unsafe { resource.resource.get_unchecked() }
+    }
+
+    /// Returns resource of type `T`.
+    /// Returns `None` if resource is missing or was not locked mutably.
+    pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        let resource = self.find_resource(TypeId::of::<T>())?;
+        match resource.kind {
+            AccessKind::Shared => None,
+            // SAFETY: Resource is locked mutably and the access is borrowed exclusively.
+            AccessKind::Mutable => unsafe { (**resource.resource.cell().get()).downcast_mut() },
+        }
+    }
+
+    fn find_resource(&self, id: TypeId) -> Option<&AsyncResourceAccess<'a>> {
+        let index = self
+            .resources
+            .binary_search_by_key(&id, |resource| resource.id)
+            .ok()?;
+        Some(&self.resources[index])
+    }
+
+    /// Returns iterator over views of all entities that match the view.
+    ///
+    /// # Panics
+    ///
+    /// Iteration panics if view requires access that was not locked
+    /// or borrows same components in conflicting ways.
+    pub fn query<'b, V>(&'b mut self) -> impl Iterator<Item = <V as View<'b>>::EntityView> + 'b
+    where
+        V: View<'b> + Access + Default + 'b,
+    {
+        self.query_with(V::default())
+    }
+
+    /// Returns iterator over views of all entities that match specified view.
+    ///
+    /// # Panics
+    ///
+    /// Iteration panics if view requires access that was not locked
+    /// or borrows same components in conflicting ways.
+    pub fn query_with<'b, V>(
+        &'b mut self,
+        view: V,
+    ) -> impl Iterator<Item = <V as View<'b>>::EntityView> + 'b
+    where
+        V: View<'b> + Access + 'b,
+    {
+        let archetypes: &'b [AsyncArchetypeAccess<'b>] = &self.archetypes;
+        query_archetypes(archetypes.iter().map(AsyncArchetypeAccess::get), view)
+    }
+
+    /// Returns iterator over views of all chunks that match the view.
+    /// Each chunk view contains slices of components of all entities in the chunk.
+    ///
+    /// # Panics
+    ///
+    /// Iteration panics if view requires access that was not locked
+    /// or borrows same components in conflicting ways.
+    pub fn query_chunks<'b, V>(
+        &'b mut self,
+    ) -> impl Iterator<Item = <V as View<'b>>::ChunkView> + 'b
+    where
+        V: View<'b> + Access + Default + 'b,
+    {
+        self.query_chunks_with(V::default())
+    }
+
+    /// Returns iterator over views of all chunks that match specified view.
+    /// Each chunk view contains slices of components of all entities in the chunk.
+    ///
+    /// # Panics
+    ///
+    /// Iteration panics if view requires access that was not locked
+    /// or borrows same components in conflicting ways.
+    pub fn query_chunks_with<'b, V>(
+        &'b mut self,
+        view: V,
+    ) -> impl Iterator<Item = <V as View<'b>>::ChunkView> + 'b
+    where
+        V: View<'b> + Access + 'b,
+    {
+        let archetypes: &'b [AsyncArchetypeAccess<'b>] = &self.archetypes;
+        query_archetype_chunks(archetypes.iter().map(AsyncArchetypeAccess::get), view)
+    }
+
+    /// Returns parallel iterator over views of all chunks that match the view.
+    /// Chunks are yielded in the same order as by `query_chunks`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view requires access that was not locked
+    /// or borrows same components in conflicting ways.
+    #[cfg(feature = "parallel")]
+    pub fn par_iter_chunks<'b, V>(
+        &'b mut self,
+    ) -> impl IndexedParallelIterator<Item = <V as View<'b>>::ChunkView> + 'b
+    where
+        V: View<'b> + Access + Default + 'b,
+        V::ChunkView: Send,
+    {
+        self.par_iter_chunks_with(V::default())
+    }
+
+    /// Returns parallel iterator over views of all chunks that match specified view.
+    /// Chunks are yielded in the same order as by `query_chunks_with`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view requires access that was not locked
+    /// or borrows same components in conflicting ways.
+    #[cfg(feature = "parallel")]
+    pub fn par_iter_chunks_with<'b, V>(
+        &'b mut self,
+        view: V,
+    ) -> impl IndexedParallelIterator<Item = <V as View<'b>>::ChunkView> + 'b
+    where
+        V: View<'b> + Access + 'b,
+        V::ChunkView: Send,
+    {
+        let archetypes: &'b [AsyncArchetypeAccess<'b>] = &self.archetypes;
+        par_query_archetype_chunks(archetypes.iter().map(AsyncArchetypeAccess::get), view)
+    }
+
+    /// Calls `f` with views of all entities that match the view.
+    /// Chunks are processed in parallel on rayon's thread pool.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view requires access that was not locked
+    /// or borrows same components in conflicting ways.
+    #[cfg(feature = "parallel")]
+    pub fn par_for_each<'b, V, F>(&'b mut self, f: F)
+    where
+        V: View<'b> + Access + Default + 'b,
+        V::ChunkRefs: Send,
+        F: Fn(<V as View<'b>>::EntityView) + Sync,
+    {
+        self.par_for_each_with(V::default(), f)
+    }
+
+    /// Calls `f` with views of all entities that match specified view.
+    /// Chunks are processed in parallel on rayon's thread pool.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view requires access that was not locked
+    /// or borrows same components in conflicting ways.
+    #[cfg(feature = "parallel")]
+    pub fn par_for_each_with<'b, V, F>(&'b mut self, view: V, f: F)
+    where
+        V: View<'b> + Access + 'b,
+        V::ChunkRefs: Send,
+        F: Fn(<V as View<'b>>::EntityView) + Sync,
+    {
+        let archetypes: &'b [AsyncArchetypeAccess<'b>] = &self.archetypes;
+        par_for_each_archetypes(archetypes.iter().map(AsyncArchetypeAccess::get), view, f)
+    }
+
+    /// Returns view of specified entity.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    pub fn get<'b, V>(&'b mut self, entity: Entity) -> Result<<V as View<'b>>::EntityView, GetError>
+    where
+        V: View<'b> + Access + Default,
+    {
+        self.get_with(V::default(), entity)
+    }
+
+    /// Returns specified view of specified entity.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    pub fn get_with<'b, V>(
+        &'b mut self,
+        view: V,
+        entity: Entity,
+    ) -> Result<<V as View<'b>>::EntityView, GetError>
+    where
+        V: View<'b> + Access,
+    {
+        let (archetype, index) = self.locate(&view, entity)?;
+        let archetypes: &'b [AsyncArchetypeAccess<'b>] = &self.archetypes;
+
+        // Location is valid while world is borrowed.
+        unsafe {
+            if !matches_one(&view, archetypes[archetype].get(), index) {
+                return Err(GetError::MissingComponents);
             }
-            .flatten()
-        })
+            Ok(fetch_one(&view, archetypes[archetype].get(), index))
+        }
+    }
+
+    /// Returns views of several distinct entities at once.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    pub fn get_many_mut<'b, V, const N: usize>(
+        &'b mut self,
+        entities: [Entity; N],
+    ) -> Result<[<V as View<'b>>::EntityView; N], GetError>
+    where
+        V: View<'b> + Access + Default,
+    {
+        self.get_many_mut_with(V::default(), entities)
+    }
+
+    /// Returns specified views of several distinct entities at once.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    pub fn get_many_mut_with<'b, V, const N: usize>(
+        &'b mut self,
+        view: V,
+        entities: [Entity; N],
+    ) -> Result<[<V as View<'b>>::EntityView; N], GetError>
+    where
+        V: View<'b> + Access,
+    {
+        // Distinct entities never share components.
+        for (i, entity) in entities.iter().enumerate() {
+            if entities[..i].contains(entity) {
+                return Err(GetError::DuplicateEntity);
+            }
+        }
+
+        let mut locations = [(0, 0); N];
+        for (location, &entity) in locations.iter_mut().zip(&entities) {
+            *location = self.locate(&view, entity)?;
+        }
+
+        let archetypes: &'b [AsyncArchetypeAccess<'b>] = &self.archetypes;
+
+        // Locations are valid while world is borrowed.
+        // Borrows are released after each check.
+        for &(archetype, index) in &locations {
+            if !unsafe { matches_one(&view, archetypes[archetype].get(), index) } {
+                return Err(GetError::MissingComponents);
+            }
+        }
+
+        // Borrows are released after each fetch.
+        Ok(locations.map(|(archetype, index)| unsafe {
+            fetch_one(&view, archetypes[archetype].get(), index)
+        }))
+    }
+
+    /// Returns index of the locked archetype and index of the entity in it.
+    fn locate(&self, view: &impl Access, entity: Entity) -> Result<(usize, usize), GetError> {
+        let location = self
+            .world
+            .entities()
+            .locate(entity)
+            .ok_or(GetError::NoSuchEntity)?;
+
+        let data = self
+            .world
+            .archetypes()
+            .get(location.archetype)
+            .ok_or(GetError::MissingComponents)?;
+
+        if !view.matches(data.storage().archetype()) {
+            return Err(GetError::MissingComponents);
+        }
+
+        let archetype = self
+            .archetypes
+            .binary_search_by_key(&location.archetype, |archetype| archetype.index)
+            .map_err(|_| GetError::NotLocked)?;
+
+        if !self.archetypes[archetype].get().is_granted(view) {
+            return Err(GetError::NotLocked);
+        }
+
+        Ok((archetype, location.index))
     }
 }
 
 impl World {
-    async fn lock(&self, access: impl Access) -> AsyncWorldAccess<'_> {
-        if self.archetypes().len() == 0 {
-            AsyncWorldAccess {
-                archetypes: Vec::new(),
-            }
+    /// Returns future that locks components of all archetypes
+    /// and resources requested by the access.
+    /// Missing resources are not locked.
+    ///
+    /// Locks are acquired all at once.
+    /// If some lock is not available, already acquired locks are released
+    /// and acquisition is retried when that lock is released.
+    ///
+    /// Locked components and resources can't be read through the world
+    /// while it is borrowed by the lock.
+    ///
+    /// ```compile_fail
+    /// use alex::{write, World};
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn((0u32,));
+    /// let lock = world.lock((write::<u32>(),));
+    /// world.get_ref::<u32>(entity).unwrap();
+    /// drop(lock);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use alex::{write_res, World};
+    ///
+    /// let mut world = World::new();
+    /// world.insert_resource(0u32);
+    /// let lock = world.lock((write_res::<u32>(),));
+    /// world.resource::<u32>().unwrap();
+    /// drop(lock);
+    /// ```
+    pub fn lock<A>(&self, access: A) -> LockFuture<'_, A>
+    where
+        A: Access,
+    {
+        LockFuture {
+            world: self,
+            access,
+            waiting: None,
+        }
+    }
+}
+
+/// Future returned by `World::lock`.
+/// Resolves into `AsyncWorldAccess` when all locks are acquired.
+///
+/// Does not hold any locks while pending,
+/// so it can be dropped any time.
+/// While pending it is registered as waiter on single contended lock.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct LockFuture<'a, A> {
+    world: &'a World,
+    access: A,
+    waiting: Option<(&'a AsyncLock, u64)>,
+}
+
+impl<'a, A> LockFuture<'a, A> {
+    fn cancel_waiting(&mut self) {
+        if let Some((lock, waiter)) = self.waiting.take() {
+            lock.cancel(waiter);
+        }
+    }
+
+    /// Polls the lock.
+    /// When pending, registers as waiter on this lock only.
+    /// Caller must release already acquired locks.
+    fn poll_lock(
+        &mut self,
+        ctx: &mut Context,
+        lock: &'a AsyncLock,
+        kind: AccessKind,
+    ) -> Poll<Guard<'a>> {
+        // Reuse registration if this lock is the one we wait for.
+        let registered = match self.waiting {
+            Some((waiting, _)) => core::ptr::eq(waiting, lock),
+            None => false,
+        };
+        let mut waiter = if registered {
+            self.waiting.map(|(_, waiter)| waiter)
         } else {
-            let mut result: Vec<AsyncArchetypeAccess<'_>> = Vec::new();
-
-            for archetype in self.archetypes() {
-                let storage = archetype.storage();
-                let archetype_components = storage.archetype().components();
-
-                let components: Box<[AccessComponent]> =
-                    access.with_accesses(storage.archetype(), |slice| slice.into());
-
-                let has_all = (|| {
-                    let mut archetype_components = archetype_components.iter();
-                    for component in &*components {
-                        for archetype_component in &mut archetype_components {
-                            match Ord::cmp(&component.id, &archetype_component.id) {
-                                Ordering::Equal => break,
-                                Ordering::Less => return false, // Component not found.
-                                Ordering::Greater => continue,
-                            }
+            None
+        };
+
+        let guard = match kind {
+            AccessKind::Mutable => lock.poll_lock_mutable(ctx, &mut waiter).map(Guard::Mutable),
+            AccessKind::Shared => lock.poll_lock_shared(ctx, &mut waiter).map(Guard::Shared),
+        };
+
+        match guard {
+            Poll::Ready(guard) => {
+                if registered {
+                    // Registration is removed by the lock.
+                    self.waiting = None;
+                }
+                Poll::Ready(guard)
+            }
+            Poll::Pending => {
+                // Wait only for this lock.
+                if !registered {
+                    self.cancel_waiting();
+                }
+                self.waiting = waiter.map(|waiter| (lock, waiter));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<A> Drop for LockFuture<'_, A> {
+    fn drop(&mut self) {
+        self.cancel_waiting();
+    }
+}
+
+impl<A> Unpin for LockFuture<'_, A> {}
+
+impl<'a, A> Future for LockFuture<'a, A>
+where
+    A: Access,
+{
+    type Output = AsyncWorldAccess<'a>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<AsyncWorldAccess<'a>> {
+        let me = self.get_mut();
+        let world = me.world;
+
+        let mut requested = Vec::new();
+        me.access
+            .with_resources(|resources| requested.extend_from_slice(resources));
+
+        // Accesses are sorted by id, keep strongest access for each resource.
+        requested.dedup_by(|next, prev| {
+            if next.id == prev.id {
+                if next.kind == AccessKind::Mutable {
+                    prev.kind = AccessKind::Mutable;
+                }
+                true
+            } else {
+                false
+            }
+        });
+
+        let mut resources = Vec::new();
+        for access in requested {
+            if let Some(resource) = world.resources().get(&access.id) {
+                match me.poll_lock(ctx, resource.lock(), access.kind) {
+                    Poll::Ready(guard) => resources.push(AsyncResourceAccess {
+                        id: access.id,
+                        _guard: guard,
+                        kind: access.kind,
+                        resource,
+                    }),
+                    // Acquired locks are released on return.
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+        }
+
+        let mut archetypes = Vec::new();
+        let required = world.required_signature(&me.access);
+
+        for (index, archetype) in world.archetypes().iter().enumerate() {
+            let storage = archetype.storage();
+
+            if storage.len() == 0
+                || !archetype.has_all(required.as_ref())
+                || !archetype_matches(&me.access, storage.archetype())
+            {
+                continue;
+            }
+
+            let granted: Vec<Cell<usize>> = storage
+                .archetype()
+                .components()
+                .iter()
+                .map(|_| Cell::new(0))
+                .collect();
+
+            // Collect strongest access for each component.
+            me.access.with_accesses(storage.archetype(), |components| {
+                for component in components {
+                    if let Some(index) = world
+                        .component_id(component.id)
+                        .and_then(|id| storage.component_index(id))
+                    {
+                        let value = match component.kind {
+                            AccessKind::Shared => usize::MAX - 1,
+                            AccessKind::Mutable => usize::MAX,
+                        };
+                        if granted[index].get() < value {
+                            granted[index].set(value);
                         }
                     }
-                    true
-                })();
-
-                if has_all {
-                    let mut guards = Vec::new();
-                    let mut granted = Vec::new();
-                    let mut archetype_components = archetype_components.iter();
-                    let mut locks = archetype.locks().iter();
-
-                    for component in &*components {
-                        for (archetype_component, lock) in
-                            Iterator::zip(&mut archetype_components, &mut locks)
-                        {
-                            match Ord::cmp(&component.id, &archetype_component.id) {
-                                Ordering::Equal => {
-                                    match component.kind {
-                                        AccessKind::Mutable => {
-                                            guards.push(Guard::Mutable(lock.lock_mutable().await));
-                                            granted.push(Cell::new(usize::MAX));
-                                        }
-                                        AccessKind::Shared => {
-                                            guards.push(Guard::Shared(lock.lock_shared().await));
-                                            granted.push(Cell::new(usize::MAX - 1));
-                                        }
-                                    }
-                                    break;
-                                }
-                                Ordering::Less => unsafe { unreachable_unchecked() },
-                                Ordering::Greater => continue,
-                            }
+                }
+            });
+
+            // Change ticks are read under shared lock unless stronger one is requested.
+            me.access.with_ticks(storage.archetype(), |ids| {
+                for &id in ids {
+                    if let Some(index) = world
+                        .component_id(id)
+                        .and_then(|id| storage.component_index(id))
+                    {
+                        if granted[index].get() == 0 {
+                            granted[index].set(usize::MAX - 1);
                         }
                     }
+                }
+            });
+
+            let mut guards = Vec::new();
+
+            for (granted, lock) in Iterator::zip(granted.iter(), archetype.locks()) {
+                let kind = match granted.get() {
+                    0 => continue,
+                    usize::MAX => AccessKind::Mutable,
+                    _ => AccessKind::Shared,
+                };
 
-                    result.push(AsyncArchetypeAccess {
-                        guards,
-                        granted,
-                        storage,
-                    })
+                match me.poll_lock(ctx, lock, kind) {
+                    Poll::Ready(guard) => guards.push(guard),
+                    // Acquired locks are released on return.
+                    Poll::Pending => return Poll::Pending,
                 }
             }
 
-            AsyncWorldAccess { archetypes: result }
+            archetypes.push(AsyncArchetypeAccess {
+                index,
+                _guards: guards,
+                granted,
+                storage,
+                registry: world.registry(),
+                entities: world.entities(),
+                tick: world.tick(),
+            });
         }
+
+        me.cancel_waiting();
+        Poll::Ready(AsyncWorldAccess {
+            world,
+            archetypes,
+            resources,
+        })
     }
 }
-
-// struct AsyncWorldLock<'a, A> {
-//     world: &'a World,
-//     archetype_checked: bool,
-//     archetype_offset: usize,
-//     component_offset: usize,
-//     archetype_access_cache: Vec<Cell<usize>>,
-//     world_access_cache: Vec<AsyncArchetypeAccess<'a>>,
-//     access: A,
-// }
-
-// impl<A> Unpin for AsyncWorldLock<'_, A> {}
-
-// impl<'a, A> Future for AsyncWorldLock<'a, A>
-// where
-//     A: Access,
-// {
-//     type Output = AsyncWorldAccess<'a>;
-//     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<AsyncWorldAccess<'a>> {
-//         let me = self.get_mut();
-
-//         loop {
-//             debug_assert!(me.world.archetypes().len() > me.archetype_offset);
-//             let archetype = unsafe { me.world.archetypes().get_unchecked(me.archetype_offset) };
-//             let storage = archetype.storage();
-
-//             debug_assert_eq!(
-//                 storage.archetype().components().len(),
-//                 archetype.accesses().len()
-//             );
-
-//             let archetype_checked = &mut me.archetype_checked;
-//             let component_offset = &mut me.component_offset;
-//             let archetype_access_cache = &mut me.archetype_access_cache;
-
-//             let ready = me.access.with_accesses(storage.archetype(), |components| {
-//                 if archetype.accesses().is_empty() || components.is_empty() {
-//                     return Poll::Ready(Some({
-//                         AsyncArchetypeAccess {
-//                             granted: Vec::new(),
-//                             storage,
-//                         }
-//                     }));
-//                 }
-
-//                 let archetype_components = storage.archetype().components();
-
-//                 if !*archetype_checked {
-//                     let mut archetype_components = archetype_components.iter();
-//                     for component in components {
-//                         for archetype_component in &mut archetype_components {
-//                             match Ord::cmp(&component.id, &archetype_component.id) {
-//                                 Ordering::Equal => break,
-//                                 Ordering::Less => return Poll::Ready(None), // Component not found.
-//                                 Ordering::Greater => continue,
-//                             }
-//                         }
-//                     }
-//                     *archetype_checked = true;
-//                 }
-
-//                 debug_assert!(components.len() >= archetype_access_cache.len());
-//                 debug_assert!(archetype.accesses().len() >= *component_offset);
-
-//                 loop {
-//                     let component =
-//                         unsafe { archetype_components.get_unchecked(*component_offset) };
-
-//                     let access_component =
-//                         unsafe { components.get_unchecked(archetype_access_cache.len()) };
-
-//                     match Ord::cmp(&component.id, &access_component.id) {
-//                         Ordering::Equal => {
-//                             let access =
-//                                 unsafe { archetype.accesses().get_unchecked(*component_offset) };
-//                             let mut guard = access.lock();
-
-//                             if !guard.borrow_dyn(ctx, access_component.kind) {
-//                                 return Poll::Pending;
-//                             }
-
-//                             archetype_access_cache.push(Cell::new(match access_component.kind {
-//                                 AccessKind::Shared => usize::MAX - 1,
-//                                 AccessKind::Mutable => usize::MAX,
-//                             }));
-
-//                             if components.len() == archetype_access_cache.len() + 1 {
-//                                 return Poll::Ready(Some(AsyncArchetypeAccess {
-//                                     granted: replace(archetype_access_cache, Vec::new()),
-//                                     storage: archetype.storage(),
-//                                 }));
-//                             }
-//                             *component_offset += 1;
-//                             debug_assert!(archetype.accesses().len() > *component_offset);
-//                         }
-//                         Ordering::Less => unreachable_unchecked(),
-//                         Ordering::Greater => {
-//                             *component_offset += 1;
-//                             debug_assert!(archetype.accesses().len() > *component_offset);
-//                         }
-//                     }
-//                 }
-//             });
-
-//             match ready {
-//                 Poll::Pending => return Poll::Pending,
-//                 Poll::Ready(archetype_access) => {
-//                     if let Some(archetype_access) = archetype_access {
-//                         me.world_access_cache.push(archetype_access);
-//                     }
-
-//                     if me.world.archetypes().len() == me.archetype_offset + 1 {
-//                         return Poll::Ready(AsyncWorldAccess {
-//                             archetypes: replace(&mut me.world_access_cache, Vec::new()),
-//                         });
-//                     }
-
-//                     me.archetype_checked = false;
-//                     me.archetype_offset += 1;
-//                 }
-//             }
-//         }
-//     }
-// }
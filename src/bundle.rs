@@ -1,5 +1,8 @@
 use {
-    crate::{archetype::UninitComponents, component::ComponentInfo},
+    crate::{
+        archetype::{TakeComponents, UninitComponents},
+        component::{Component, ComponentInfo},
+    },
     core::any::{type_name, TypeId},
 };
 
@@ -11,17 +14,17 @@ use {
 pub trait Bundle {
     /// Calls closure with slice of component type ids.
     /// Components must not be repeated.
-    /// Slice must be sorted by component alignment descended and then type id.
+    /// Slice must be sorted by type id.
     fn with_ids<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T;
 
     /// Calls closure with slice of component infos.
     /// Components must not be repeated.
-    /// Slice should be sorted by component alignment descended and then type id.
+    /// Slice should be sorted by type id.
     fn with_components<T>(&self, f: impl FnOnce(&[ComponentInfo]) -> T) -> T;
 
     /// Calls closure with slice of component type names.
     /// Components must not be repeated.
-    /// Slice should be sorted by component alignment descended and then type id.
+    /// Slice should be sorted by type id.
     fn with_type_names<T>(&self, f: impl FnOnce(&[&'static str]) -> T) -> T;
 
     /// Initialize components.
@@ -29,8 +32,26 @@ pub trait Bundle {
     fn init_components(self, uninit: UninitComponents<'_>);
 }
 
-macro_rules! const_tree_for_token {
-    ($a:tt, $($output:tt)*) => { $($output)* }
+/// Bundle with statically known set of components.
+/// Allows taking components back out of ECS.
+/// This trait is implemented for tuples.
+///
+/// Can be safely implemented manually.
+pub trait StaticBundle: Bundle + Sized {
+    /// Calls closure with slice of component type ids.
+    /// Slice must be equal to one provided by `Bundle::with_ids`.
+    fn static_with_ids<T>(f: impl FnOnce(&[TypeId]) -> T) -> T;
+
+    /// Tuple with optional value for each component of the bundle.
+    type Optional;
+
+    /// Take components.
+    /// Provided `TakeComponents` contains same set of components that `Self::static_with_ids` provides into closure.
+    fn take_components(take: TakeComponents<'_>) -> Self;
+
+    /// Take components that are present.
+    /// Provided `TakeComponents` contains subset of components that `Self::static_with_ids` provides into closure.
+    fn take_optional(take: TakeComponents<'_>) -> Self::Optional;
 }
 
 macro_rules! impl_component_source_for_tuple {
@@ -50,16 +71,26 @@ macro_rules! impl_component_source_for_tuple {
 
             fn init_components(self, _: UninitComponents<'_>) {}
         }
+
+        impl StaticBundle for () {
+            fn static_with_ids<T>(f: impl FnOnce(&[TypeId]) -> T) -> T {
+                f(&[])
+            }
+
+            type Optional = ();
+
+            fn take_components(_: TakeComponents<'_>) {}
+
+            fn take_optional(_: TakeComponents<'_>) {}
+        }
     };
     ($($a:ident),+) => {
         impl<$($a),+> Bundle for ($($a,)+)
         where
-            $($a: 'static,)+
+            $($a: Component,)+
         {
             fn with_ids<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T {
-                let mut type_ids = [$(TypeId::of::<$a>(),)+];
-                type_ids.sort_unstable();
-                f(&type_ids)
+                <Self as StaticBundle>::static_with_ids(f)
             }
 
             fn with_components<T>(&self, f: impl FnOnce(&[ComponentInfo]) -> T) -> T {
@@ -97,6 +128,31 @@ macro_rules! impl_component_source_for_tuple {
                 )+
             }
         }
+
+        impl<$($a),+> StaticBundle for ($($a,)+)
+        where
+            $($a: Component,)+
+        {
+            fn static_with_ids<T>(f: impl FnOnce(&[TypeId]) -> T) -> T {
+                let mut type_ids = [$(TypeId::of::<$a>(),)+];
+                type_ids.sort_unstable();
+                f(&type_ids)
+            }
+
+            type Optional = ($(Option<$a>,)+);
+
+            fn take_components(mut take: TakeComponents<'_>) -> Self {
+                ($(
+                    take.take_some::<$a>(),
+                )+)
+            }
+
+            fn take_optional(mut take: TakeComponents<'_>) -> Self::Optional {
+                ($(
+                    take.take_optional::<$a>(),
+                )+)
+            }
+        }
     };
 }
 
@@ -1,30 +1,75 @@
-use core::{
-    alloc::Layout,
-    any::{type_name, TypeId},
-    cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd},
-    fmt::{self, Display},
-    hash::{Hash, Hasher},
-    ptr::{drop_in_place, NonNull},
+use {
+    crate::{entity::Entity, util::TypeIdMap, world::World},
+    alloc::vec::Vec,
+    core::{
+        alloc::Layout,
+        any::{type_name, TypeId},
+        cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd},
+        fmt::{self, Display},
+        hash::{Hash, Hasher},
+        ptr::{drop_in_place, NonNull},
+    },
 };
 
+/// Types that can be stored in the `World` as components.
+///
+/// `World` is `Send` and `Sync` and components are accessed
+/// from whatever thread holds the lock,
+/// so components must be `Send + Sync`.
+/// Types that can't leave their thread can be wrapped into `NonSend`.
+///
+/// Implemented for all suitable types.
+pub trait Component: Send + Sync + 'static {}
+
+impl<T> Component for T where T: Send + Sync + 'static {}
+
+/// Hook called with the world and the entity whose component is affected.
+pub type ComponentHook = fn(&mut World, Entity);
+
 #[derive(Clone, Copy, Debug)]
 pub struct ComponentInfo {
     id: TypeId,
     layout: Layout,
     name: &'static str,
     drop_in_place: unsafe fn(NonNull<u8>),
+    on_add: Option<ComponentHook>,
+    on_insert: Option<ComponentHook>,
+    on_remove: Option<ComponentHook>,
 }
 
 impl ComponentInfo {
-    pub fn new<T: 'static>() -> Self {
+    pub fn new<T: Component>() -> Self {
         ComponentInfo {
             id: TypeId::of::<T>(),
             layout: Layout::new::<T>(),
             name: type_name::<T>(),
             drop_in_place: erased_drop_in_place::<T>,
+            on_add: None,
+            on_insert: None,
+            on_remove: None,
         }
     }
 
+    /// Sets hook called after component is added to an entity that didn't have it.
+    pub fn on_add(mut self, hook: ComponentHook) -> Self {
+        self.on_add = Some(hook);
+        self
+    }
+
+    /// Sets hook called after component is added or overwritten.
+    /// Runs after `on_add` hooks of the same operation.
+    pub fn on_insert(mut self, hook: ComponentHook) -> Self {
+        self.on_insert = Some(hook);
+        self
+    }
+
+    /// Sets hook called before component is removed or its entity is despawned.
+    /// Component is still accessible when hook runs.
+    pub fn on_remove(mut self, hook: ComponentHook) -> Self {
+        self.on_remove = Some(hook);
+        self
+    }
+
     pub fn is<T: 'static>(&self) -> bool {
         self.id == TypeId::of::<T>()
     }
@@ -44,6 +89,22 @@ impl ComponentInfo {
     pub(crate) fn drop_in_place(&self) -> unsafe fn(NonNull<u8>) {
         self.drop_in_place
     }
+
+    pub fn add_hook(&self) -> Option<ComponentHook> {
+        self.on_add
+    }
+
+    pub fn insert_hook(&self) -> Option<ComponentHook> {
+        self.on_insert
+    }
+
+    pub fn remove_hook(&self) -> Option<ComponentHook> {
+        self.on_remove
+    }
+
+    fn has_hooks(&self) -> bool {
+        self.on_add.is_some() || self.on_insert.is_some() || self.on_remove.is_some()
+    }
 }
 
 impl Display for ComponentInfo {
@@ -86,6 +147,61 @@ impl Ord for ComponentInfo {
     }
 }
 
+/// Dense id of component type registered in a `World`.
+/// Ids are assigned in order of registration starting from zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ComponentId(u32);
+
+impl ComponentId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Registry of component types known to a `World`.
+#[derive(Default)]
+pub(crate) struct ComponentRegistry {
+    ids: TypeIdMap<ComponentId>,
+    infos: Vec<ComponentInfo>,
+
+    /// Whether any component type has hooks.
+    hooked: bool,
+}
+
+impl ComponentRegistry {
+    /// Returns id of registered component type.
+    pub fn get(&self, id: TypeId) -> Option<ComponentId> {
+        self.ids.get(&id).copied()
+    }
+
+    /// Returns id of the component type registering it if needed.
+    pub fn register(&mut self, info: &ComponentInfo) -> ComponentId {
+        let infos = &mut self.infos;
+        *self.ids.entry(info.id()).or_insert_with(|| {
+            let id = ComponentId(infos.len() as u32);
+            infos.push(*info);
+            id
+        })
+    }
+
+    /// Registers component type replacing its hooks.
+    pub fn register_hooks(&mut self, info: &ComponentInfo) -> ComponentId {
+        let id = self.register(info);
+        self.infos[id.index()] = *info;
+        self.hooked |= info.has_hooks();
+        id
+    }
+
+    /// Returns info of component type with id of specified index.
+    pub fn info(&self, index: usize) -> &ComponentInfo {
+        &self.infos[index]
+    }
+
+    pub fn has_hooks(&self) -> bool {
+        self.hooked
+    }
+}
+
 unsafe fn erased_drop_in_place<T>(ptr: NonNull<u8>) {
     drop_in_place(ptr.as_ptr() as *mut T)
 }
@@ -3,6 +3,7 @@ use {
     alloc::vec::Vec,
     core::{
         convert::TryFrom as _,
+        mem::replace,
         sync::atomic::{AtomicI64, AtomicUsize, Ordering::*},
     },
     spin::Mutex,
@@ -50,8 +51,8 @@ pub struct EntityLocations {
     ready_counter: AtomicI64,
     ready_entries: Vec<usize>,
 
-    drop: Queue<usize, SyncPush>,
-    drop_slow: Mutex<Vec<usize>>,
+    drop: Queue<Entity, SyncPush>,
+    drop_slow: Mutex<Vec<Entity>>,
 }
 
 impl Default for EntityLocations {
@@ -92,7 +93,7 @@ impl EntityLocations {
         } else if ready_counter < underflow_treshold() {
             Err(TooManyEntities)
         } else {
-            let index = (-ready_counter) as usize;
+            let index = self.entries.len() + (-ready_counter) as usize;
             Ok(Entity {
                 index,
                 gen: Generation::new(),
@@ -112,7 +113,7 @@ impl EntityLocations {
                 usize::try_from(ready_counter).is_ok(),
                 "Never stores value greater than `ready_counter.len()`"
             );
-            let index = self.ready_entries[ready_counter as usize];
+            let index = self.ready_entries[ready_counter as usize - 1];
             Entity {
                 index,
                 gen: self.entries[index].gen,
@@ -139,6 +140,15 @@ impl EntityLocations {
         }
     }
 
+    /// Returns entity with specified index.
+    /// Entity must be spawned and not yet despawned.
+    pub fn get_entity(&self, index: usize) -> Entity {
+        Entity {
+            index,
+            gen: self.entries[index].gen,
+        }
+    }
+
     /// Changes location of an entity.
     pub fn relocate(&mut self, entity: Entity, location: Location) {
         self.flush_spawns();
@@ -148,11 +158,19 @@ impl EntityLocations {
         entry.location = location;
     }
 
+    /// Changes location of an entity with specified index.
+    /// Used when entity is moved inside archetype storage.
+    pub fn relocate_index(&mut self, index: usize, location: Location) {
+        self.flush_spawns();
+
+        self.entries[index].location = location;
+    }
+
     pub fn despawn(&self, entity: Entity) -> bool {
         if entity.gen == self.get_generation(entity.index) {
             // Schedule entity dropping.
-            if self.drop.sync_push(entity.index).is_err() {
-                self.drop_slow.lock().push(entity.index);
+            if let Err(entity) = self.drop.sync_push(entity) {
+                self.drop_slow.lock().push(entity);
             }
             true
         } else {
@@ -163,13 +181,22 @@ impl EntityLocations {
     pub fn despawn_mut(&mut self, entity: Entity) -> bool {
         if entity.gen == self.get_generation(entity.index) {
             // Schedule entity dropping.
-            self.drop.push(entity.index);
+            self.drop.push(entity);
             true
         } else {
             false
         }
     }
 
+    /// Moves entities scheduled for despawn into `out`.
+    /// They are not despawned unless scheduled again.
+    pub fn drain_despawned(&mut self, out: &mut Vec<Entity>) {
+        out.append(self.drop_slow.get_mut());
+        while let Some(entity) = self.drop.pop() {
+            out.push(entity);
+        }
+    }
+
     /// Must be called before any mutable operation.
     pub fn flush_spawns(&mut self) {
         let counter = *self.ready_counter.get_mut();
@@ -183,7 +210,7 @@ impl EntityLocations {
             debug_assert!(usize::try_from(-counter).is_ok());
             let excess = (-counter) as usize;
 
-            self.entries.extend((0..excess).map(|i| Entry {
+            self.entries.extend((0..excess).map(|_| Entry {
                 location: Location::EMPTY,
                 gen: Generation::new(),
             }));
@@ -191,7 +218,9 @@ impl EntityLocations {
     }
 
     /// Must be called after each systems dispatch.
-    pub fn flush(&mut self, mut drop_fn: impl FnMut(Location)) {
+    /// Calls `drop_fn` for each despawned entity and its location.
+    /// `drop_fn` returns index of an entity moved into freed location if any.
+    pub fn flush(&mut self, mut drop_fn: impl FnMut(Entity, Location) -> Option<usize>) {
         self.flush_spawns();
 
         let drop = &mut self.drop;
@@ -204,18 +233,45 @@ impl EntityLocations {
             .drain(..)
             .chain(core::iter::from_fn(|| drop.pop()));
 
-        for index in todrop {
-            drop_fn(self.entries[index].location);
-            self.entries[index].gen.inc();
+        for entity in todrop {
+            let entry = &mut self.entries[entity.index];
+            if entry.gen != entity.gen {
+                // Entity despawned twice.
+                continue;
+            }
 
-            self.ready_entries.push(index);
-            let count = i64::try_from(self.ready_entries.len()).unwrap_or(i64::MAX);
-            *self.ready_counter.get_mut() = count;
+            let location = replace(&mut entry.location, Location::EMPTY);
+            entry.gen.inc();
+
+            if let Some(moved) = drop_fn(entity, location) {
+                self.entries[moved].location = location;
+            }
+
+            self.ready_entries.push(entity.index);
         }
 
+        *self.ready_counter.get_mut() = saturating_cast(self.ready_entries.len());
+
         self.drop.reserve(slow_drop_len);
     }
 
+    /// Despawns all entities.
+    pub fn clear(&mut self) {
+        self.flush_spawns();
+
+        self.drop_slow.get_mut().clear();
+        while self.drop.pop().is_some() {}
+
+        self.ready_entries.clear();
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            entry.location = Location::EMPTY;
+            entry.gen.inc();
+            self.ready_entries.push(index);
+        }
+
+        *self.ready_counter.get_mut() = saturating_cast(self.ready_entries.len());
+    }
+
     fn get_generation(&self, index: usize) -> Generation {
         self.entries.get(index).map_or(Generation::new(), |e| e.gen)
     }
@@ -0,0 +1,158 @@
+use {
+    crate::{
+        component::Component,
+        resource::{read_res, write_res, ReadRes, Resource, WriteRes},
+    },
+    alloc::vec::Vec,
+    core::{
+        cmp::min,
+        marker::PhantomData,
+        mem::swap,
+        sync::atomic::{AtomicU64, Ordering::Relaxed},
+    },
+};
+
+static NEXT_CHANNEL_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Channel of events of type `T`.
+/// Stored in the `World` as a resource.
+///
+/// Events are double-buffered.
+/// Each rotation drops events sent before the previous one,
+/// so events are kept for at least one full maintenance cycle.
+pub struct Events<T> {
+    /// Events sent before last rotation.
+    previous: Vec<T>,
+
+    /// Events sent since last rotation.
+    current: Vec<T>,
+
+    /// Sequence number of the first event in `previous`.
+    start: u64,
+
+    /// Unique id of the channel.
+    id: u64,
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Events<T> {
+    pub fn new() -> Self {
+        Events {
+            previous: Vec::new(),
+            current: Vec::new(),
+            start: 0,
+            id: NEXT_CHANNEL_ID.fetch_add(1, Relaxed),
+        }
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+    }
+
+    /// Returns number of retained events.
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops events sent before previous rotation.
+    /// Called by `World::maintain` for channels added to the world.
+    pub fn update(&mut self) {
+        self.start += self.previous.len() as u64;
+        self.previous.clear();
+        swap(&mut self.previous, &mut self.current);
+    }
+
+    /// Drops all retained events.
+    pub fn clear(&mut self) {
+        self.start += self.len() as u64;
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// Returns sequence number of the next event to be sent.
+    fn end(&self) -> u64 {
+        self.start + self.len() as u64
+    }
+}
+
+/// Shared access to events of type `T`.
+/// Locks the channel along with components when used with `World::lock`.
+pub type EventReader<T> = ReadRes<Events<T>>;
+
+/// Mutable access to events of type `T`.
+/// Locks the channel along with components when used with `World::lock`.
+pub type EventWriter<T> = WriteRes<Events<T>>;
+
+pub fn read_events<T>() -> EventReader<T> {
+    read_res()
+}
+
+pub fn write_events<T>() -> EventWriter<T> {
+    write_res()
+}
+
+/// Cursor of one events reader.
+///
+/// Each reader keeps its own cursor, so any number of readers
+/// see every event once if they read at least once per maintenance cycle.
+/// Readers that fall behind miss dropped events.
+pub struct EventCursor<T> {
+    /// Id of the channel the cursor belongs to.
+    channel: Option<u64>,
+
+    /// Sequence number of the next event to read.
+    next: u64,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for EventCursor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> EventCursor<T> {
+    /// Returns new cursor that starts from the oldest retained event.
+    pub fn new() -> Self {
+        EventCursor {
+            channel: None,
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns events sent since previous read.
+    ///
+    /// Reading from another channel, e.g. one of another world, resets the cursor.
+    pub fn read<'a>(&mut self, events: &'a Events<T>) -> impl Iterator<Item = &'a T> + 'a {
+        if self.channel != Some(events.id) {
+            self.channel = Some(events.id);
+            self.next = 0;
+        }
+
+        let skip = min(self.next.saturating_sub(events.start), events.len() as u64);
+        self.next = events.end();
+
+        events
+            .previous
+            .iter()
+            .chain(events.current.iter())
+            .skip(skip as usize)
+    }
+}
+
+/// Rotates events channel stored in the resource.
+pub(crate) fn update_events<T: Component>(resource: &mut Resource) {
+    if let Some(events) = resource.get_mut::<Events<T>>() {
+        events.update();
+    }
+}
@@ -6,19 +6,42 @@
 
 extern crate alloc;
 
+macro_rules! const_tree_for_token {
+    ($a:tt, $($output:tt)*) => { $($output)* }
+}
+
 mod archetype;
 mod r#async;
 mod bundle;
 mod component;
 mod entity;
+mod events;
+#[cfg(feature = "std")]
+mod non_send;
+mod prepared;
 mod query;
+mod removed;
+mod resource;
 mod util;
 mod world;
 
 pub use self::{
-    archetype::{Archetype, UninitComponents},
-    bundle::Bundle,
+    archetype::{Archetype, TakeComponents, UninitComponents},
+    bundle::{Bundle, StaticBundle},
+    component::{Component, ComponentHook, ComponentId, ComponentInfo},
     entity::Entity,
-    query::{read, write, Access, AccessComponent, AccessKind, Read, Write},
-    world::World,
+    events::{read_events, write_events, EventCursor, EventReader, EventWriter, Events},
+    prepared::{PreparedAccess, PreparedLockFuture, PreparedQuery},
+    query::{
+        added, changed, read, with, without, write, Access, AccessComponent, AccessKind, AccessOne,
+        Added, And, AnyOf, Changed, DynamicChunk, DynamicComponent, DynamicQuery, DynamicRow,
+        Entities, EntitySlice, Or, Read, View, With, Without, Write,
+    },
+    r#async::{AsyncWorldAccess, LockFuture},
+    removed::RemovedComponents,
+    resource::{read_res, write_res, ReadRes, WriteRes},
+    world::{GetError, NoSuchEntity, RemoveError, World},
 };
+
+#[cfg(feature = "std")]
+pub use self::non_send::NonSend;
@@ -0,0 +1,137 @@
+use {
+    core::{
+        fmt::{self, Debug},
+        mem::ManuallyDrop,
+        ops::{Deref, DerefMut},
+    },
+    std::thread::{self, ThreadId},
+};
+
+/// Wrapper that allows storing values that are not `Send` or `Sync` in the `World`.
+///
+/// Wrapped value can be accessed only from the thread that created the wrapper.
+/// Access from other threads panics or returns `None` for fallible methods.
+///
+/// If wrapper is dropped on another thread, wrapped value is leaked
+/// as it can't be dropped there.
+pub struct NonSend<T> {
+    value: ManuallyDrop<T>,
+    owner: ThreadId,
+}
+
+/// Wrapped value is accessible only from the owner thread,
+/// so wrapper itself can be freely sent and shared.
+unsafe impl<T> Send for NonSend<T> {}
+unsafe impl<T> Sync for NonSend<T> {}
+
+impl<T> NonSend<T> {
+    /// Wraps value owned by current thread.
+    pub fn new(value: T) -> Self {
+        NonSend {
+            value: ManuallyDrop::new(value),
+            owner: thread::current().id(),
+        }
+    }
+
+    /// Returns id of the thread that owns the value.
+    pub fn owner(&self) -> ThreadId {
+        self.owner
+    }
+
+    /// Returns `true` if called from the thread that owns the value.
+    pub fn is_owner(&self) -> bool {
+        thread::current().id() == self.owner
+    }
+
+    /// Returns reference to wrapped value.
+    /// Returns `None` if called not from the owner thread.
+    pub fn try_get(&self) -> Option<&T> {
+        if self.is_owner() {
+            Some(&self.value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns mutable reference to wrapped value.
+    /// Returns `None` if called not from the owner thread.
+    pub fn try_get_mut(&mut self) -> Option<&mut T> {
+        if self.is_owner() {
+            Some(&mut self.value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns reference to wrapped value.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called not from the owner thread.
+    pub fn get(&self) -> &T {
+        self.try_get().unwrap_or_else(|| wrong_thread())
+    }
+
+    /// Returns mutable reference to wrapped value.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called not from the owner thread.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.try_get_mut().unwrap_or_else(|| wrong_thread())
+    }
+
+    /// Unwraps the value.
+    /// Returns wrapper back if called not from the owner thread.
+    pub fn into_inner(self) -> Result<T, Self> {
+        if self.is_owner() {
+            let mut me = ManuallyDrop::new(self);
+            Ok(unsafe { ManuallyDrop::take(&mut me.value) })
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T> Deref for NonSend<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T> DerefMut for NonSend<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<T> Drop for NonSend<T> {
+    fn drop(&mut self) {
+        if self.is_owner() {
+            unsafe { ManuallyDrop::drop(&mut self.value) }
+        }
+    }
+}
+
+impl<T> Debug for NonSend<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_get() {
+            Some(value) => fmt.debug_tuple("NonSend").field(value).finish(),
+            None => fmt
+                .debug_struct("NonSend")
+                .field("owner", &self.owner)
+                .finish(),
+        }
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn wrong_thread() -> ! {
+    panic!("`NonSend` value accessed not from the owner thread")
+}
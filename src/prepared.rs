@@ -0,0 +1,519 @@
+use {
+    crate::{
+        query::{
+            archetype_matches, assert_no_conflicts,
+            iter::{ArchetypeChunkIter, ArchetypeEntityIter},
+            Access, AccessKind, ArchetypeAccess, View,
+        },
+        util::AsyncLock,
+        world::World,
+    },
+    alloc::vec::Vec,
+    core::{
+        cell::Cell,
+        future::Future,
+        mem::forget,
+        ops::Range,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+/// Component column accessed by prepared query.
+struct PreparedColumn {
+    /// Index of the component in the archetype.
+    component: usize,
+
+    /// Strongest access requested for the component.
+    kind: AccessKind,
+
+    /// Whether lock for the column is held.
+    locked: bool,
+}
+
+/// Archetype matched by prepared query.
+struct PreparedArchetype {
+    /// Index of the archetype in the world.
+    index: usize,
+
+    /// Range in `PreparedQuery::columns`.
+    columns: Range<usize>,
+
+    /// Range in `PreparedQuery::granted`.
+    /// One element per component of the archetype.
+    granted: Range<usize>,
+}
+
+/// Query that caches archetypes matching the view
+/// and columns accessed in each of them.
+///
+/// Only archetypes created since last use are matched,
+/// so querying and locking do not allocate once set of archetypes is stable.
+pub struct PreparedQuery<V> {
+    view: V,
+
+    /// Id of the world the plan is built for.
+    world: Option<u64>,
+
+    /// Archetype generation of the world when plan was last updated.
+    generation: usize,
+
+    archetypes: Vec<PreparedArchetype>,
+    columns: Vec<PreparedColumn>,
+    granted: Vec<Cell<usize>>,
+}
+
+impl<V> Default for PreparedQuery<V>
+where
+    V: Access + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> PreparedQuery<V>
+where
+    V: Access,
+{
+    /// Returns prepared query for the view.
+    pub fn new() -> Self
+    where
+        V: Default,
+    {
+        Self::with_view(V::default())
+    }
+
+    /// Returns prepared query for specified view.
+    pub fn with_view(view: V) -> Self {
+        PreparedQuery {
+            view,
+            world: None,
+            generation: 0,
+            archetypes: Vec::new(),
+            columns: Vec::new(),
+            granted: Vec::new(),
+        }
+    }
+
+    /// Returns the view of this query.
+    pub fn view(&self) -> &V {
+        &self.view
+    }
+
+    /// Returns the view of this query for modification, e.g. updating `Changed` filters.
+    /// View must keep matching same archetypes.
+    pub fn view_mut(&mut self) -> &mut V {
+        &mut self.view
+    }
+
+    /// Returns iterator over views of all entities that match the view.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    pub fn query<'a>(
+        &'a mut self,
+        world: &'a mut World,
+    ) -> impl Iterator<Item = <V as View<'a>>::EntityView> + 'a
+    where
+        V: View<'a>,
+    {
+        self.update(world);
+        self.grant_all();
+
+        let me: &'a Self = self;
+        query_prepared(&me.view, me.archetype_accesses(world))
+    }
+
+    /// Returns iterator over views of all chunks that match the view.
+    /// Each chunk view contains slices of components of all entities in the chunk.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    pub fn query_chunks<'a>(
+        &'a mut self,
+        world: &'a mut World,
+    ) -> impl Iterator<Item = <V as View<'a>>::ChunkView> + 'a
+    where
+        V: View<'a>,
+    {
+        self.update(world);
+        self.grant_all();
+
+        let me: &'a Self = self;
+        query_prepared_chunks(&me.view, me.archetype_accesses(world))
+    }
+
+    /// Returns future that locks components of all archetypes matched by the query.
+    ///
+    /// Locks are acquired all at once.
+    /// If some lock is not available, already acquired locks are released
+    /// and acquisition is retried when that lock is released.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    pub fn lock<'a>(&'a mut self, world: &'a World) -> PreparedLockFuture<'a, V> {
+        self.update(world);
+
+        for granted in &mut self.granted {
+            *granted.get_mut() = 0;
+        }
+
+        PreparedLockFuture {
+            world,
+            query: Some(self),
+            waiting: None,
+        }
+    }
+
+    /// Matches archetypes created since last update.
+    fn update(&mut self, world: &World) {
+        if self.world != Some(world.id()) {
+            self.world = Some(world.id());
+            self.generation = 0;
+            self.archetypes.clear();
+            self.columns.clear();
+            self.granted.clear();
+        }
+
+        let generation = world.archetype_generation();
+        if self.generation == generation {
+            return;
+        }
+
+        let required = world.required_signature(&self.view);
+
+        for (index, data) in world.archetypes().iter().enumerate().skip(self.generation) {
+            let storage = data.storage();
+            let archetype = storage.archetype();
+
+            if !data.has_all(required.as_ref()) || !archetype_matches(&self.view, archetype) {
+                continue;
+            }
+
+            assert_no_conflicts(&self.view, archetype);
+
+            let columns = &mut self.columns;
+            let start = columns.len();
+
+            self.view.with_accesses(archetype, |components| {
+                // Accesses are sorted by id, so duplicates are adjacent.
+                for component in components {
+                    let index = match world
+                        .component_id(component.id)
+                        .and_then(|id| storage.component_index(id))
+                    {
+                        Some(index) => index,
+                        None => continue,
+                    };
+
+                    match columns[start..].last_mut() {
+                        Some(last) if last.component == index => {
+                            if component.kind == AccessKind::Mutable {
+                                last.kind = AccessKind::Mutable;
+                            }
+                        }
+                        _ => columns.push(PreparedColumn {
+                            component: index,
+                            kind: component.kind,
+                            locked: false,
+                        }),
+                    }
+                }
+            });
+
+            // Change ticks are read under shared lock unless stronger one is requested.
+            self.view.with_ticks(archetype, |ids| {
+                for &id in ids {
+                    let index = match world
+                        .component_id(id)
+                        .and_then(|id| storage.component_index(id))
+                    {
+                        Some(index) => index,
+                        None => continue,
+                    };
+
+                    if let Err(at) = columns[start..].binary_search_by_key(&index, |c| c.component)
+                    {
+                        columns.insert(
+                            start + at,
+                            PreparedColumn {
+                                component: index,
+                                kind: AccessKind::Shared,
+                                locked: false,
+                            },
+                        );
+                    }
+                }
+            });
+
+            let granted = self.granted.len();
+            self.granted
+                .extend(archetype.components().iter().map(|_| Cell::new(0)));
+
+            self.archetypes.push(PreparedArchetype {
+                index,
+                columns: start..self.columns.len(),
+                granted: granted..self.granted.len(),
+            });
+        }
+
+        self.generation = generation;
+    }
+
+    /// Grants all accesses to all archetypes.
+    fn grant_all(&mut self) {
+        for granted in &mut self.granted {
+            *granted.get_mut() = usize::MAX;
+        }
+    }
+
+    /// Returns accesses to non-empty matched archetypes.
+    fn archetype_accesses<'a>(
+        &'a self,
+        world: &'a World,
+    ) -> impl Iterator<Item = ArchetypeAccess<'a>> + 'a {
+        let granted = &self.granted[..];
+        self.archetypes.iter().filter_map(move |prepared| {
+            let storage = world.archetypes()[prepared.index].storage();
+            if storage.len() == 0 {
+                return None;
+            }
+
+            Some(ArchetypeAccess::new(
+                &granted[prepared.granted.clone()],
+                storage,
+                world.registry(),
+                world.entities(),
+                world.tick(),
+            ))
+        })
+    }
+
+    /// Releases all held locks.
+    fn release(&mut self, world: &World) {
+        for prepared in &self.archetypes {
+            let locks = world.archetypes()[prepared.index].locks();
+            for column in &mut self.columns[prepared.columns.clone()] {
+                if column.locked {
+                    column.locked = false;
+
+                    // Lock is acquired and its guard is forgotten.
+                    unsafe {
+                        match column.kind {
+                            AccessKind::Shared => locks[column.component].release_shared(),
+                            AccessKind::Mutable => locks[column.component].release_mutable(),
+                        }
+                    }
+                }
+            }
+        }
+
+        for granted in &mut self.granted {
+            *granted.get_mut() = 0;
+        }
+    }
+}
+
+/// Future returned by `PreparedQuery::lock`.
+/// Resolves into `PreparedAccess` when all locks are acquired.
+///
+/// Does not hold any locks while pending,
+/// so it can be dropped any time.
+/// While pending it is registered as waiter on single contended lock.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PreparedLockFuture<'a, V> {
+    world: &'a World,
+    query: Option<&'a mut PreparedQuery<V>>,
+    waiting: Option<(&'a AsyncLock, u64)>,
+}
+
+impl<'a, V> PreparedLockFuture<'a, V> {
+    fn cancel_waiting(&mut self) {
+        if let Some((lock, waiter)) = self.waiting.take() {
+            lock.cancel(waiter);
+        }
+    }
+}
+
+impl<V> Drop for PreparedLockFuture<'_, V> {
+    fn drop(&mut self) {
+        self.cancel_waiting();
+    }
+}
+
+impl<V> Unpin for PreparedLockFuture<'_, V> {}
+
+impl<'a, V> Future for PreparedLockFuture<'a, V>
+where
+    V: Access,
+{
+    type Output = PreparedAccess<'a, V>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<PreparedAccess<'a, V>> {
+        let me = self.get_mut();
+        let world = me.world;
+        let query = me
+            .query
+            .as_mut()
+            .expect("`PreparedLockFuture` polled after completion");
+
+        for prepared in &query.archetypes {
+            let data = &world.archetypes()[prepared.index];
+
+            // Storage can't change while world is borrowed.
+            if data.storage().len() == 0 {
+                continue;
+            }
+
+            let granted = &query.granted[prepared.granted.clone()];
+
+            for column in &mut query.columns[prepared.columns.clone()] {
+                let lock = &data.locks()[column.component];
+
+                // Reuse registration if this lock is the one we wait for.
+                let registered = match me.waiting {
+                    Some((waiting, _)) => core::ptr::eq(waiting, lock),
+                    None => false,
+                };
+                let mut waiter = if registered {
+                    me.waiting.map(|(_, waiter)| waiter)
+                } else {
+                    None
+                };
+
+                // Guards are forgotten and locks are released by `PreparedQuery::release`.
+                let locked = match column.kind {
+                    AccessKind::Shared => lock.poll_lock_shared(ctx, &mut waiter).map(forget),
+                    AccessKind::Mutable => lock.poll_lock_mutable(ctx, &mut waiter).map(forget),
+                };
+
+                match locked {
+                    Poll::Ready(()) => {
+                        if registered {
+                            // Registration is removed by the lock.
+                            me.waiting = None;
+                        }
+                        column.locked = true;
+                        granted[column.component].set(match column.kind {
+                            AccessKind::Shared => usize::MAX - 1,
+                            AccessKind::Mutable => usize::MAX,
+                        });
+                    }
+                    Poll::Pending => {
+                        // Wait only for this lock.
+                        if registered {
+                            me.waiting = None;
+                        } else if let Some((lock, waiter)) = me.waiting.take() {
+                            lock.cancel(waiter);
+                        }
+                        me.waiting = waiter.map(|waiter| (lock, waiter));
+
+                        query.release(world);
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+
+        me.cancel_waiting();
+        Poll::Ready(PreparedAccess {
+            world,
+            query: me.query.take().unwrap(),
+        })
+    }
+}
+
+/// Accesses to components granted by `PreparedQuery::lock`.
+/// Locks are held until this value is dropped.
+pub struct PreparedAccess<'a, V: Access> {
+    world: &'a World,
+    query: &'a mut PreparedQuery<V>,
+}
+
+impl<V> Drop for PreparedAccess<'_, V>
+where
+    V: Access,
+{
+    fn drop(&mut self) {
+        self.query.release(self.world);
+    }
+}
+
+impl<'a, V> PreparedAccess<'a, V>
+where
+    V: Access,
+{
+    /// Returns iterator over views of all entities that match the view.
+    pub fn query<'b>(&'b mut self) -> impl Iterator<Item = <V as View<'b>>::EntityView> + 'b
+    where
+        V: View<'b>,
+    {
+        let query: &'b PreparedQuery<V> = self.query;
+        query_prepared(&query.view, query.archetype_accesses(self.world))
+    }
+
+    /// Returns iterator over views of all chunks that match the view.
+    /// Each chunk view contains slices of components of all entities in the chunk.
+    pub fn query_chunks<'b>(&'b mut self) -> impl Iterator<Item = <V as View<'b>>::ChunkView> + 'b
+    where
+        V: View<'b>,
+    {
+        let query: &'b PreparedQuery<V> = self.query;
+        query_prepared_chunks(&query.view, query.archetype_accesses(self.world))
+    }
+}
+
+/// Returns iterator over views of all entities in archetypes.
+/// Archetypes must be already matched and checked for conflicts.
+fn query_prepared<'a, V, I>(
+    view: &'a V,
+    archetypes: I,
+) -> impl Iterator<Item = <V as View<'a>>::EntityView> + 'a
+where
+    V: View<'a>,
+    I: Iterator<Item = ArchetypeAccess<'a>> + 'a,
+{
+    archetypes.flat_map(move |archetype| {
+        let storage = archetype.storage();
+
+        ArchetypeEntityIter {
+            raw_chunks: storage.raw_chunks().iter(),
+            len: storage.len(),
+            chunk_capacity: storage.chunk_capacity(),
+            refs: view.acquire(archetype),
+        }
+        .flatten()
+    })
+}
+
+/// Returns iterator over views of all chunks in archetypes.
+/// Archetypes must be already matched and checked for conflicts.
+fn query_prepared_chunks<'a, V, I>(
+    view: &'a V,
+    archetypes: I,
+) -> impl Iterator<Item = <V as View<'a>>::ChunkView> + 'a
+where
+    V: View<'a>,
+    I: Iterator<Item = ArchetypeAccess<'a>> + 'a,
+{
+    archetypes.flat_map(move |archetype| {
+        let storage = archetype.storage();
+
+        ArchetypeChunkIter {
+            raw_chunks: storage.raw_chunks().iter(),
+            len: storage.len(),
+            chunk_capacity: storage.chunk_capacity(),
+            refs: view.acquire(archetype),
+        }
+    })
+}
@@ -1,5 +1,10 @@
 use {
-    crate::archetype::{Archetype, ArchetypeStorage},
+    crate::{
+        archetype::{Archetype, ArchetypeStorage, TickOffsets},
+        component::ComponentRegistry,
+        entity::EntityLocations,
+    },
+    alloc::vec::Vec,
     core::{any::TypeId, cell::Cell, marker::PhantomData, ptr::NonNull},
 };
 
@@ -25,8 +30,38 @@ pub struct AccessComponent {
     pub kind: AccessKind,
 }
 
+/// Declare access to at most one component.
 pub trait AccessOne {
-    fn access(&self, archetype: &Archetype) -> AccessComponent;
+    /// Returns access required for the archetype.
+    /// Request may depend on archetype.
+    fn access(&self, archetype: &Archetype) -> Option<AccessComponent>;
+
+    /// Checks that archetype matches.
+    /// By default archetype must contain requested component.
+    fn matches(&self, archetype: &Archetype) -> bool {
+        match self.access(archetype) {
+            Some(access) => archetype.contains(access.id),
+            None => true,
+        }
+    }
+
+    /// Returns id of component that every matching archetype contains.
+    /// Used to skip archetypes quickly before calling `matches`.
+    fn required(&self) -> Option<TypeId> {
+        None
+    }
+
+    /// Returns id of component whose change ticks are read.
+    /// Reading change ticks does not conflict with accesses to the component.
+    fn ticks(&self, _archetype: &Archetype) -> Option<TypeId> {
+        None
+    }
+
+    /// Returns access to a resource.
+    /// Resources are locked together with components by `World::lock`.
+    fn resource(&self) -> Option<AccessComponent> {
+        None
+    }
 }
 
 /// Declare components and access kind.
@@ -35,6 +70,34 @@ pub trait Access {
     /// Request may depend on archetype.
     fn with_accesses<T>(&self, archetype: &Archetype, f: impl FnOnce(&[AccessComponent]) -> T)
         -> T;
+
+    /// Checks that archetype matches.
+    /// By default archetype must contain all requested components.
+    fn matches(&self, archetype: &Archetype) -> bool {
+        self.with_accesses(archetype, |components| {
+            components
+                .iter()
+                .all(|component| archetype.contains(component.id))
+        })
+    }
+
+    /// Calls closure providing ids of components that every matching archetype contains.
+    /// Used to skip archetypes quickly before calling `matches`.
+    /// Listing fewer components is always correct.
+    fn with_required<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T {
+        f(&[])
+    }
+
+    /// Calls closure providing ids of components whose change ticks are read.
+    /// Reading change ticks does not conflict with accesses to the components.
+    fn with_ticks<T>(&self, _archetype: &Archetype, f: impl FnOnce(&[TypeId]) -> T) -> T {
+        f(&[])
+    }
+
+    /// Calls closure providing list of accesses to resources.
+    fn with_resources<T>(&self, f: impl FnOnce(&[AccessComponent]) -> T) -> T {
+        f(&[])
+    }
 }
 
 impl<A> Access for A
@@ -46,12 +109,158 @@ where
         archetype: &Archetype,
         f: impl FnOnce(&[AccessComponent]) -> T,
     ) -> T {
-        f(core::slice::from_ref(&self.access(archetype)))
+        match self.access(archetype) {
+            Some(access) => f(core::slice::from_ref(&access)),
+            None => f(&[]),
+        }
     }
+
+    fn matches(&self, archetype: &Archetype) -> bool {
+        AccessOne::matches(self, archetype)
+    }
+
+    fn with_required<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T {
+        match self.required() {
+            Some(id) => f(core::slice::from_ref(&id)),
+            None => f(&[]),
+        }
+    }
+
+    fn with_ticks<T>(&self, archetype: &Archetype, f: impl FnOnce(&[TypeId]) -> T) -> T {
+        match self.ticks(archetype) {
+            Some(id) => f(core::slice::from_ref(&id)),
+            None => f(&[]),
+        }
+    }
+
+    fn with_resources<T>(&self, f: impl FnOnce(&[AccessComponent]) -> T) -> T {
+        match self.resource() {
+            Some(access) => f(core::slice::from_ref(&access)),
+            None => f(&[]),
+        }
+    }
+}
+
+macro_rules! impl_for_tuple {
+    () => {
+        impl Access for () {
+            fn with_accesses<T>(&self, _: &Archetype, f: impl FnOnce(&[AccessComponent]) -> T) -> T {
+                f(&[])
+            }
+
+            fn matches(&self, _: &Archetype) -> bool {
+                true
+            }
+        }
+    };
+
+    ($($a:ident),+ $(,)?) => {
+        impl<$($a),+> Access for ($($a,)+)
+        where
+            $($a: Access,)+
+        {
+            fn with_accesses<T>(
+                &self,
+                archetype: &Archetype,
+                f: impl FnOnce(&[AccessComponent]) -> T,
+            ) -> T {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                let mut accesses = Vec::new();
+                $(
+                    $a.with_accesses(archetype, |a| accesses.extend_from_slice(a));
+                )+
+                accesses.sort_unstable_by_key(|a| a.id);
+                f(&accesses)
+            }
+
+            fn matches(&self, archetype: &Archetype) -> bool {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                $($a.matches(archetype))&&+
+            }
+
+            fn with_required<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                let mut required = Vec::new();
+                $(
+                    $a.with_required(|r| required.extend_from_slice(r));
+                )+
+                f(&required)
+            }
+
+            fn with_ticks<T>(&self, archetype: &Archetype, f: impl FnOnce(&[TypeId]) -> T) -> T {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                let mut ticks = Vec::new();
+                $(
+                    $a.with_ticks(archetype, |t| ticks.extend_from_slice(t));
+                )+
+                f(&ticks)
+            }
+
+            fn with_resources<T>(&self, f: impl FnOnce(&[AccessComponent]) -> T) -> T {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                let mut resources = Vec::new();
+                $(
+                    $a.with_resources(|r| resources.extend_from_slice(r));
+                )+
+                resources.sort_unstable_by_key(|a| a.id);
+                f(&resources)
+            }
+        }
+    };
+}
+
+impl_for_tuple!();
+impl_for_tuple!(A);
+impl_for_tuple!(A, B);
+impl_for_tuple!(A, B, C);
+impl_for_tuple!(A, B, C, D);
+impl_for_tuple!(A, B, C, D, E);
+impl_for_tuple!(A, B, C, D, E, F);
+impl_for_tuple!(A, B, C, D, E, F, G);
+impl_for_tuple!(A, B, C, D, E, F, G, H);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Checks that archetype matches the access.
+pub fn archetype_matches(access: &impl Access, archetype: &Archetype) -> bool {
+    access.matches(archetype)
+}
+
+/// Checks that access does not request mutable access to a component
+/// along with any other access to the same component.
+///
+/// # Panics
+///
+/// This function panics if conflicting accesses are requested.
+pub(crate) fn assert_no_conflicts(access: &impl Access, archetype: &Archetype) {
+    access.with_accesses(archetype, |components| {
+        // Accesses are sorted by id, so duplicates are adjacent.
+        for pair in components.windows(2) {
+            if pair[0].id == pair[1].id
+                && (pair[0].kind == AccessKind::Mutable || pair[1].kind == AccessKind::Mutable)
+            {
+                let name = archetype
+                    .components()
+                    .iter()
+                    .find(|component| component.id() == pair[0].id)
+                    .map_or("<unknown>", |component| component.name());
+
+                panic!("Conflicting accesses to components of type `{}`", name);
+            }
+        }
+    })
 }
 
 pub struct ArchetypeRef<'a, T> {
     offset: usize,
+    ticks: Option<TickOffsets>,
     marker: PhantomData<fn() -> &'a T>,
     unlock: &'a Cell<usize>,
 }
@@ -66,17 +275,25 @@ impl<'a, T> ArchetypeRef<'a, T> {
     pub unsafe fn get(&self, raw: NonNull<u8>) -> NonNull<T> {
         NonNull::new_unchecked(raw.as_ptr().add(self.offset) as *mut T)
     }
+
+    /// Returns offsets of change ticks of the borrowed column.
+    /// Zero-sized components have no change ticks.
+    pub fn ticks(&self) -> Option<TickOffsets> {
+        self.ticks
+    }
 }
 
 pub struct ArchetypeRefMut<'a, T> {
     offset: usize,
+    ticks: Option<TickOffsets>,
+    tick: u64,
     marker: PhantomData<fn() -> &'a mut T>,
     unlock: &'a Cell<usize>,
 }
 
 impl<'a, T> Drop for ArchetypeRefMut<'a, T> {
     fn drop(&mut self) {
-        debug_assert_eq!(self.unlock.get(), 0);
+        debug_assert_eq!(self.unlock.get(), 1);
         self.unlock.set(usize::MAX);
     }
 }
@@ -85,10 +302,53 @@ impl<'a, T> ArchetypeRefMut<'a, T> {
     pub unsafe fn get(&self, raw: NonNull<u8>) -> NonNull<T> {
         NonNull::new_unchecked(raw.as_ptr().add(self.offset) as *mut T)
     }
+
+    /// Returns change ticks of the borrowed column in the chunk.
+    /// Zero-sized components have no change ticks.
+    pub unsafe fn change_ticks(&self, raw: NonNull<u8>) -> Option<ChangeTicks> {
+        let ticks = self.ticks?;
+        Some(ChangeTicks::new(raw, ticks, self.tick))
+    }
+}
+
+/// Marks components in mutably borrowed column of one chunk as changed.
+#[derive(Clone, Copy)]
+pub struct ChangeTicks {
+    /// Tick of the next row.
+    row: NonNull<u64>,
+    chunk: NonNull<u64>,
+    tick: u64,
+}
+
+impl ChangeTicks {
+    unsafe fn new(raw: NonNull<u8>, ticks: TickOffsets, tick: u64) -> Self {
+        ChangeTicks {
+            row: NonNull::new_unchecked(raw.as_ptr().add(ticks.changed(0)) as *mut u64),
+            chunk: NonNull::new_unchecked(raw.as_ptr().add(ticks.chunk_changed()) as *mut u64),
+            tick,
+        }
+    }
+
+    /// Marks next `count` rows as changed.
+    pub unsafe fn mark(&self, count: usize) {
+        for i in 0..count {
+            *self.row.as_ptr().add(i) = self.tick;
+        }
+        if count > 0 {
+            *self.chunk.as_ptr() = self.tick;
+        }
+    }
+
+    /// Skips `count` rows.
+    pub unsafe fn skip(&mut self, count: usize) {
+        self.row = NonNull::new_unchecked(self.row.as_ptr().add(count));
+    }
 }
 
 pub struct AccessDyn<'a> {
     offset: usize,
+    ticks: Option<TickOffsets>,
+    tick: u64,
     id: TypeId,
     kind: AccessKind,
     unlock: &'a Cell<usize>,
@@ -99,7 +359,7 @@ impl<'a> Drop for AccessDyn<'a> {
         match self.kind {
             AccessKind::Shared => self.unlock.set(self.unlock.get() + 1),
             AccessKind::Mutable => {
-                debug_assert_eq!(self.unlock.get(), 0);
+                debug_assert_eq!(self.unlock.get(), 1);
                 self.unlock.set(usize::MAX);
             }
         }
@@ -111,18 +371,95 @@ impl<'a> AccessDyn<'a> {
         debug_assert_eq!(self.id, TypeId::of::<T>());
         NonNull::new_unchecked(raw.as_ptr().add(self.offset) as *mut T)
     }
+
+    pub unsafe fn get_erased(&self, raw: NonNull<u8>) -> NonNull<u8> {
+        NonNull::new_unchecked(raw.as_ptr().add(self.offset))
+    }
+
+    pub fn kind(&self) -> AccessKind {
+        self.kind
+    }
+
+    /// Returns change ticks of the borrowed column in the chunk
+    /// if access is mutable and component is not zero-sized.
+    pub unsafe fn change_ticks(&self, raw: NonNull<u8>) -> Option<ChangeTicks> {
+        match (self.kind, self.ticks) {
+            (AccessKind::Mutable, Some(ticks)) => Some(ChangeTicks::new(raw, ticks, self.tick)),
+            _ => None,
+        }
+    }
 }
 
 /// Accesses granted to an archetype.
 #[derive(Clone, Copy)]
 pub struct ArchetypeAccess<'a> {
+    /// Per column: `0` if not granted, `usize::MAX` if granted mutably,
+    /// `1` while borrowed mutably and number of shared borrows left otherwise.
     granted: &'a [Cell<usize>],
     storage: &'a ArchetypeStorage,
+    registry: &'a ComponentRegistry,
+    entities: &'a EntityLocations,
+
+    /// Tick to mark mutably borrowed components with.
+    tick: u64,
 }
 
 impl<'a> ArchetypeAccess<'a> {
-    pub(crate) fn new(granted: &'a [Cell<usize>], storage: &'a ArchetypeStorage) -> Self {
-        ArchetypeAccess { granted, storage }
+    pub(crate) fn new(
+        granted: &'a [Cell<usize>],
+        storage: &'a ArchetypeStorage,
+        registry: &'a ComponentRegistry,
+        entities: &'a EntityLocations,
+        tick: u64,
+    ) -> Self {
+        ArchetypeAccess {
+            granted,
+            storage,
+            registry,
+            entities,
+            tick,
+        }
+    }
+
+    pub(crate) fn storage(&self) -> &'a ArchetypeStorage {
+        self.storage
+    }
+
+    pub(crate) fn entities(&self) -> &'a EntityLocations {
+        self.entities
+    }
+
+    /// Returns index of the column of components with specified type id.
+    pub(crate) fn component_index(&self, id: TypeId) -> Option<usize> {
+        self.storage.component_index(self.registry.get(id)?)
+    }
+
+    /// Checks that all accesses requested for this archetype are granted.
+    pub(crate) fn is_granted(&self, access: &impl Access) -> bool {
+        let archetype = self.storage.archetype();
+
+        let components = access.with_accesses(archetype, |components| {
+            components
+                .iter()
+                .all(|component| match self.component_index(component.id) {
+                    Some(index) => match (self.granted[index].get(), component.kind) {
+                        (usize::MAX, _) => true,
+                        (0, _) => false,
+                        (_, AccessKind::Shared) => true,
+                        (_, AccessKind::Mutable) => false,
+                    },
+                    None => false,
+                })
+        });
+
+        // Any granted access allows reading change ticks.
+        components
+            && access.with_ticks(archetype, |ids| {
+                ids.iter().all(|&id| match self.component_index(id) {
+                    Some(index) => self.granted[index].get() != 0,
+                    None => false,
+                })
+            })
     }
 
     pub fn len(&self) -> usize {
@@ -136,17 +473,18 @@ impl<'a> ArchetypeAccess<'a> {
     /// Borrow shared access to the components of type `T`.
     pub fn borrow_ref<T: 'static>(&self) -> Option<ArchetypeRef<'a, T>> {
         let id = TypeId::of::<T>();
-        let index = self.storage.component_index(id)?;
+        let index = self.component_index(id)?;
         debug_assert!(self.granted.len() > index);
         let granted = unsafe { self.granted.get_unchecked(index) };
         match granted.get() {
-            0 => None,
+            0 | 1 => None,
             left => {
                 granted.set(left - 1);
 
                 Some(ArchetypeRef {
                     unlock: granted,
                     offset: unsafe { self.storage.component_offset_by_index_unchecked(index) },
+                    ticks: unsafe { self.storage.component_ticks_by_index_unchecked(index) },
                     marker: PhantomData,
                 })
             }
@@ -156,16 +494,18 @@ impl<'a> ArchetypeAccess<'a> {
     /// Borrow shared access to the components of type `T`.
     pub fn borrow_mut<T: 'static>(&self) -> Option<ArchetypeRefMut<'a, T>> {
         let id = TypeId::of::<T>();
-        let index = self.storage.component_index(id)?;
+        let index = self.component_index(id)?;
         debug_assert!(self.granted.len() > index);
         let granted = unsafe { self.granted.get_unchecked(index) };
         match granted.get() {
             usize::MAX => {
-                granted.set(0);
+                granted.set(1);
 
                 Some(ArchetypeRefMut {
                     unlock: granted,
                     offset: unsafe { self.storage.component_offset_by_index_unchecked(index) },
+                    ticks: unsafe { self.storage.component_ticks_by_index_unchecked(index) },
+                    tick: self.tick,
                     marker: PhantomData,
                 })
             }
@@ -175,16 +515,16 @@ impl<'a> ArchetypeAccess<'a> {
 
     /// Borrow shared access to the components of type `T`.
     pub fn borrow_dyn(&self, id: TypeId, kind: AccessKind) -> Option<AccessDyn<'a>> {
-        let index = self.storage.component_index(id)?;
+        let index = self.component_index(id)?;
         debug_assert!(self.granted.len() > index);
         let granted = unsafe { self.granted.get_unchecked(index) };
 
         let offset = match (granted.get(), kind) {
             (usize::MAX, AccessKind::Mutable) => {
-                granted.set(0);
+                granted.set(1);
                 unsafe { self.storage.component_offset_by_index_unchecked(index) }
             }
-            (0, _) => return None,
+            (0, _) | (1, _) => return None,
             (left, AccessKind::Shared) => {
                 granted.set(left - 1);
                 unsafe { self.storage.component_offset_by_index_unchecked(index) }
@@ -195,8 +535,23 @@ impl<'a> ArchetypeAccess<'a> {
         Some(AccessDyn {
             unlock: granted,
             offset,
+            ticks: unsafe { self.storage.component_ticks_by_index_unchecked(index) },
+            tick: self.tick,
             id,
             kind,
         })
     }
+
+    /// Returns offsets of change ticks of the components of type `T`.
+    /// Any granted access to the components allows reading their change ticks,
+    /// even while they are borrowed mutably.
+    pub fn ticks<T: 'static>(&self) -> Option<TickOffsets> {
+        let id = TypeId::of::<T>();
+        let index = self.component_index(id)?;
+        debug_assert!(self.granted.len() > index);
+        match unsafe { self.granted.get_unchecked(index) }.get() {
+            0 => None,
+            _ => unsafe { self.storage.component_ticks_by_index_unchecked(index) },
+        }
+    }
 }
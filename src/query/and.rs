@@ -1,62 +1,66 @@
-use super::access::{Access, AccessOne, ArchetypeAccess};
+use {
+    super::{
+        access::{Access, AccessComponent, ArchetypeAccess},
+        view::View,
+    },
+    crate::archetype::Archetype,
+    core::any::TypeId,
+};
 
+/// View that matches archetypes that match all of the views in tuple `T`.
+///
+/// Yields the same items as tuple `T` itself.
+/// Useful to group views inside other combinators, e.g. `Or<(And<(A, B)>, C)>`.
 pub struct And<T>(pub T);
 
-macro_rules! impl_for_tuple {
-    () => {
-        impl Access for And<()> {
-            fn with_accesses<T>(
-                &self,
-                archetype: &Archetype,
-                f: impl FnOnce(&[AccessComponent]) -> T,
-            ) -> T {
-                f(&[])
-            }
-        }
-
-        impl<'a> View<'a> for And<()> {
-            type EntityView = ();
-            type ArchetypeRefs = ();
-
-            fn acquire(&self, _: ArchetypeAccess<'a>) {}
-        }
-    };
-
-    ($($a:ident)+) => {
-        impl<$($a),+> Access for And<($($a,)+)>
-        where
-            $($a: AccessOne,)+
-        {
-            fn with_accesses<T>(
-                &self,
-                archetype: &Archetype,
-                f: impl FnOnce(&[AccessComponent]) -> T,
-            ) -> T {
-                #![allon(non_snake_case)]
-                let ($($a,)+) = self;
-                let mut accesses = [$( $a.access() )+];
-                accesses.sort_unordered_by_key(|a| a.id);
-                f(&accesses)
-            }
-        }
-
-        impl<'a $(, $a)+> View<'a> for And<($($a,)+)>
-        where
-            $($a: View<'a>,)+
-        {
-            type EntityView = ($($a::EntityView)+);
-            type ArchetypeRefs = ($($a::ArchetypeRefs)+);
-
-            fn acquire(&self, archetype: ArchetypeAccess<'a>) {
-                let ($($a,)+) = self;
-                ($($a.acquire(archetype))+)
-            }
-        }
-    };
+impl<T> Default for And<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        And(T::default())
+    }
 }
 
-impl_for_tuple!();
-impl_for_tuple!(A);
-impl_for_tuple!(A, B);
-impl_for_tuple!(A, B, C);
-impl_for_tuple!(A, B, C, D);
+impl<T> Access for And<T>
+where
+    T: Access,
+{
+    fn with_accesses<R>(
+        &self,
+        archetype: &Archetype,
+        f: impl FnOnce(&[AccessComponent]) -> R,
+    ) -> R {
+        self.0.with_accesses(archetype, f)
+    }
+
+    fn matches(&self, archetype: &Archetype) -> bool {
+        self.0.matches(archetype)
+    }
+
+    fn with_required<R>(&self, f: impl FnOnce(&[TypeId]) -> R) -> R {
+        self.0.with_required(f)
+    }
+
+    fn with_ticks<R>(&self, archetype: &Archetype, f: impl FnOnce(&[TypeId]) -> R) -> R {
+        self.0.with_ticks(archetype, f)
+    }
+
+    fn with_resources<R>(&self, f: impl FnOnce(&[AccessComponent]) -> R) -> R {
+        self.0.with_resources(f)
+    }
+}
+
+impl<'a, T> View<'a> for And<T>
+where
+    T: View<'a>,
+{
+    type EntityView = T::EntityView;
+    type ChunkView = T::ChunkView;
+    type ChunkRefs = T::ChunkRefs;
+    type ArchetypeRefs = T::ArchetypeRefs;
+
+    fn acquire(&self, archetype: ArchetypeAccess<'a>) -> T::ArchetypeRefs {
+        self.0.acquire(archetype)
+    }
+}
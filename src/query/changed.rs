@@ -0,0 +1,213 @@
+use {
+    super::{
+        access::{AccessComponent, AccessOne, ArchetypeAccess},
+        view::{ArchetypeRefs, ChunkRefs, View},
+    },
+    crate::archetype::{Archetype, TickOffsets},
+    core::{
+        any::{type_name, TypeId},
+        marker::PhantomData,
+        mem::size_of,
+        ptr::NonNull,
+    },
+};
+
+/// Filter that matches entities whose components of type `T`
+/// were borrowed mutably or added at or after specified tick.
+/// Reads change ticks without borrowing components,
+/// so it can be combined with `Write<T>`.
+///
+/// Chunks without such entities are skipped without visiting their entities.
+/// Chunk views are not filtered further and cover all entities of matching chunks.
+///
+/// Zero-sized components have no change ticks, filtering by them panics.
+pub struct Changed<T> {
+    since: u64,
+    marker: PhantomData<fn() -> T>,
+}
+
+/// Returns filter that matches entities whose components of type `T`
+/// were borrowed mutably or added at or after `since` tick.
+///
+/// Pass `World::tick` observed at the previous run to get entities changed since then.
+/// Changes made at that tick before the previous run are reported again.
+pub fn changed<T>(since: u64) -> Changed<T> {
+    Changed {
+        since,
+        marker: PhantomData,
+    }
+}
+
+impl<T> Changed<T> {
+    pub fn since(&self) -> u64 {
+        self.since
+    }
+
+    pub fn set_since(&mut self, since: u64) {
+        self.since = since;
+    }
+}
+
+/// Filter that matches entities whose components of type `T`
+/// were added at or after specified tick.
+/// Reads change ticks without borrowing components,
+/// so it can be combined with `Write<T>`.
+///
+/// Replacing component with `World::insert` keeps tick when it was added.
+/// Chunks without such entities are skipped without visiting their entities.
+/// Chunk views are not filtered further and cover all entities of matching chunks.
+///
+/// Zero-sized components have no change ticks, filtering by them panics.
+pub struct Added<T> {
+    since: u64,
+    marker: PhantomData<fn() -> T>,
+}
+
+/// Returns filter that matches entities whose components of type `T`
+/// were added at or after `since` tick.
+pub fn added<T>(since: u64) -> Added<T> {
+    Added {
+        since,
+        marker: PhantomData,
+    }
+}
+
+impl<T> Added<T> {
+    pub fn since(&self) -> u64 {
+        self.since
+    }
+
+    pub fn set_since(&mut self, since: u64) {
+        self.since = since;
+    }
+}
+
+/// Change ticks of one chunk compared against a tick.
+pub struct ChunkTicks<'a> {
+    ptr: NonNull<u64>,
+    since: u64,
+    marker: PhantomData<&'a [u64]>,
+}
+
+// Behaves like `&'a [u64]`.
+unsafe impl<'a> Send for ChunkTicks<'a> {}
+
+impl<'a> ChunkRefs for ChunkTicks<'a> {
+    type Item = ();
+    type Slice = ();
+
+    unsafe fn next(&mut self) {
+        self.skip(1);
+    }
+
+    unsafe fn slice(self, _: usize) {}
+
+    unsafe fn skip(&mut self, count: usize) {
+        self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(count));
+    }
+
+    unsafe fn matches(&self) -> bool {
+        *self.ptr.as_ptr() >= self.since
+    }
+}
+
+/// Change ticks of archetype column compared against a tick.
+pub struct ArchetypeTicks<'a, T> {
+    ticks: TickOffsets,
+    since: u64,
+    added: bool,
+    marker: PhantomData<fn() -> &'a T>,
+}
+
+impl<'a, T> ArchetypeTicks<'a, T> {
+    fn new(archetype: ArchetypeAccess<'a>, since: u64, added: bool) -> Self
+    where
+        T: 'static,
+    {
+        if size_of::<T>() == 0 {
+            panic!(
+                "Zero-sized components of type `{}` have no change ticks",
+                type_name::<T>(),
+            );
+        }
+
+        let ticks = match archetype.ticks::<T>() {
+            Some(ticks) => ticks,
+            None => panic!(
+                "Archetype missing components of type `{}`",
+                type_name::<T>(),
+            ),
+        };
+
+        ArchetypeTicks {
+            ticks,
+            since,
+            added,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> ArchetypeRefs for ArchetypeTicks<'a, T> {
+    type Item = ChunkTicks<'a>;
+
+    unsafe fn get(&self, base: NonNull<u8>) -> ChunkTicks<'a> {
+        let offset = if self.added {
+            self.ticks.added(0)
+        } else {
+            self.ticks.changed(0)
+        };
+
+        ChunkTicks {
+            ptr: NonNull::new_unchecked(base.as_ptr().add(offset) as *mut u64),
+            since: self.since,
+            marker: PhantomData,
+        }
+    }
+
+    unsafe fn matches(&self, base: NonNull<u8>) -> bool {
+        let offset = if self.added {
+            self.ticks.chunk_added()
+        } else {
+            self.ticks.chunk_changed()
+        };
+
+        *(base.as_ptr().add(offset) as *const u64) >= self.since
+    }
+}
+
+macro_rules! impl_filter {
+    ($filter:ident, $added:literal) => {
+        impl<T: 'static> AccessOne for $filter<T> {
+            fn access(&self, _: &Archetype) -> Option<AccessComponent> {
+                None
+            }
+
+            fn matches(&self, archetype: &Archetype) -> bool {
+                archetype.contains(TypeId::of::<T>())
+            }
+
+            fn required(&self) -> Option<TypeId> {
+                Some(TypeId::of::<T>())
+            }
+
+            fn ticks(&self, _: &Archetype) -> Option<TypeId> {
+                Some(TypeId::of::<T>())
+            }
+        }
+
+        impl<'a, T: 'static> View<'a> for $filter<T> {
+            type EntityView = ();
+            type ChunkView = ();
+            type ChunkRefs = ChunkTicks<'a>;
+            type ArchetypeRefs = ArchetypeTicks<'a, T>;
+
+            fn acquire(&self, archetype: ArchetypeAccess<'a>) -> ArchetypeTicks<'a, T> {
+                ArchetypeTicks::new(archetype, self.since, $added)
+            }
+        }
+    };
+}
+
+impl_filter!(Changed, false);
+impl_filter!(Added, true);
@@ -0,0 +1,293 @@
+use {
+    super::{
+        access::{Access, AccessComponent, AccessDyn, AccessKind, ArchetypeAccess, ChangeTicks},
+        view::{ArchetypeRefs, ChunkRefs, View},
+    },
+    crate::{archetype::Archetype, component::ComponentInfo},
+    alloc::{rc::Rc, vec::Vec},
+    core::{any::TypeId, ptr::NonNull},
+};
+
+/// Query built at runtime from component ids.
+///
+/// Yields `DynamicRow` for each matching entity
+/// and `DynamicChunk` for each matching chunk.
+/// Components are listed in the order they were added to the query.
+#[derive(Clone, Default)]
+pub struct DynamicQuery {
+    /// Accesses in order of addition.
+    components: Vec<AccessComponent>,
+
+    /// Same accesses sorted by id.
+    sorted: Vec<AccessComponent>,
+
+    /// Ids of accessed components followed by ids from `with`.
+    required: Vec<TypeId>,
+
+    with: Vec<TypeId>,
+    without: Vec<TypeId>,
+}
+
+impl DynamicQuery {
+    /// Returns query that matches all archetypes and borrows nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds shared access to components with specified id.
+    pub fn read(self, id: TypeId) -> Self {
+        self.access(id, AccessKind::Shared)
+    }
+
+    /// Adds exclusive access to components with specified id.
+    pub fn write(self, id: TypeId) -> Self {
+        self.access(id, AccessKind::Mutable)
+    }
+
+    /// Adds access of specified kind to components with specified id.
+    pub fn access(mut self, id: TypeId, kind: AccessKind) -> Self {
+        let access = AccessComponent { id, kind };
+        let index = self.sorted.partition_point(|a| a.id <= id);
+        self.sorted.insert(index, access);
+        self.components.push(access);
+        self.required.push(id);
+        self
+    }
+
+    /// Restricts query to archetypes with components with specified id.
+    /// Does not borrow components.
+    pub fn with(mut self, id: TypeId) -> Self {
+        self.with.push(id);
+        self.required.push(id);
+        self
+    }
+
+    /// Restricts query to archetypes without components with specified id.
+    pub fn without(mut self, id: TypeId) -> Self {
+        self.without.push(id);
+        self
+    }
+
+    /// Returns accesses in order of addition.
+    pub fn components(&self) -> &[AccessComponent] {
+        &self.components
+    }
+}
+
+impl Access for DynamicQuery {
+    fn with_accesses<T>(&self, _: &Archetype, f: impl FnOnce(&[AccessComponent]) -> T) -> T {
+        f(&self.sorted)
+    }
+
+    fn matches(&self, archetype: &Archetype) -> bool {
+        self.sorted.iter().all(|a| archetype.contains(a.id))
+            && self.with.iter().all(|&id| archetype.contains(id))
+            && !self.without.iter().any(|&id| archetype.contains(id))
+    }
+
+    fn with_required<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T {
+        f(&self.required)
+    }
+}
+
+/// Type-erased pointer to component borrowed by `DynamicQuery`.
+#[derive(Clone, Copy)]
+pub struct DynamicComponent<'a> {
+    info: &'a ComponentInfo,
+    kind: AccessKind,
+    ptr: NonNull<u8>,
+
+    /// Change ticks of mutably borrowed components.
+    ticks: Option<ChangeTicks>,
+}
+
+impl<'a> DynamicComponent<'a> {
+    /// Returns information about component type.
+    pub fn info(&self) -> &'a ComponentInfo {
+        self.info
+    }
+
+    /// Returns access kind granted to the component.
+    /// Component may be modified through the pointer only if access is mutable.
+    pub fn kind(&self) -> AccessKind {
+        self.kind
+    }
+
+    /// Returns pointer to the component.
+    /// In `DynamicChunk` points to the first component in the chunk.
+    pub fn ptr(&self) -> NonNull<u8> {
+        self.ptr
+    }
+
+    /// Returns pointer to component `index` rows further.
+    unsafe fn at(mut self, index: usize) -> Self {
+        let size = self.info.layout().size();
+        self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(size * index));
+        if let Some(ticks) = &mut self.ticks {
+            ticks.skip(index);
+        }
+        self
+    }
+
+    unsafe fn mark_changed(&self, count: usize) {
+        if let Some(ticks) = &self.ticks {
+            ticks.mark(count);
+        }
+    }
+}
+
+/// Components of one entity yielded by `DynamicQuery`.
+///
+/// Rows share column pointers of their chunk,
+/// so iteration allocates once per chunk and not per entity.
+pub struct DynamicRow<'a> {
+    columns: Rc<[DynamicComponent<'a>]>,
+    index: usize,
+}
+
+impl<'a> DynamicRow<'a> {
+    /// Returns components in order they were added to the query.
+    pub fn components(&self) -> impl ExactSizeIterator<Item = DynamicComponent<'a>> + '_ {
+        let index = self.index;
+        // SAFETY: Row is within the chunk.
+        self.columns.iter().map(move |c| unsafe { c.at(index) })
+    }
+
+    /// Returns component with specified id.
+    pub fn get(&self, id: TypeId) -> Option<DynamicComponent<'a>> {
+        self.components().find(|c| c.info.id() == id)
+    }
+}
+
+/// Components of all entities in one chunk yielded by `DynamicQuery`.
+pub struct DynamicChunk<'a> {
+    columns: Rc<[DynamicComponent<'a>]>,
+
+    /// Index of the first yielded row in the chunk.
+    start: usize,
+    len: usize,
+}
+
+impl<'a> DynamicChunk<'a> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns columns in order they were added to the query.
+    /// Each column contains `len` components laid out as an array.
+    pub fn components(&self) -> impl ExactSizeIterator<Item = DynamicComponent<'a>> + '_ {
+        let start = self.start;
+        // SAFETY: Rows from `start` are within the chunk.
+        self.columns.iter().map(move |c| unsafe { c.at(start) })
+    }
+
+    /// Returns component with specified id of entity at specified position in the chunk.
+    pub fn get(&self, id: TypeId, index: usize) -> Option<DynamicComponent<'a>> {
+        if index >= self.len {
+            return None;
+        }
+        let component = self.columns.iter().find(|c| c.info.id() == id)?;
+        Some(unsafe { component.at(self.start + index) })
+    }
+}
+
+pub struct DynamicChunkRefs<'a> {
+    columns: Rc<[DynamicComponent<'a>]>,
+
+    /// Index of the next row in the chunk.
+    index: usize,
+}
+
+impl<'a> ChunkRefs for DynamicChunkRefs<'a> {
+    type Item = DynamicRow<'a>;
+    type Slice = DynamicChunk<'a>;
+
+    unsafe fn next(&mut self) -> DynamicRow<'a> {
+        for component in self.columns.iter() {
+            component.at(self.index).mark_changed(1);
+        }
+        let row = DynamicRow {
+            columns: self.columns.clone(),
+            index: self.index,
+        };
+        self.skip(1);
+        row
+    }
+
+    unsafe fn slice(self, len: usize) -> DynamicChunk<'a> {
+        for component in self.columns.iter() {
+            component.at(self.index).mark_changed(len);
+        }
+        DynamicChunk {
+            columns: self.columns,
+            start: self.index,
+            len,
+        }
+    }
+
+    unsafe fn skip(&mut self, count: usize) {
+        self.index += count;
+    }
+}
+
+pub struct DynamicArchetypeRefs<'a> {
+    columns: Vec<(&'a ComponentInfo, AccessDyn<'a>)>,
+}
+
+impl<'a> ArchetypeRefs for DynamicArchetypeRefs<'a> {
+    type Item = DynamicChunkRefs<'a>;
+
+    unsafe fn get(&self, base: NonNull<u8>) -> DynamicChunkRefs<'a> {
+        DynamicChunkRefs {
+            columns: self
+                .columns
+                .iter()
+                .map(|(info, access)| DynamicComponent {
+                    info,
+                    kind: access.kind(),
+                    ptr: access.get_erased(base),
+                    ticks: access.change_ticks(base),
+                })
+                .collect(),
+            index: 0,
+        }
+    }
+}
+
+impl<'a> View<'a> for DynamicQuery {
+    type EntityView = DynamicRow<'a>;
+    type ChunkView = DynamicChunk<'a>;
+    type ChunkRefs = DynamicChunkRefs<'a>;
+    type ArchetypeRefs = DynamicArchetypeRefs<'a>;
+
+    fn acquire(&self, archetype: ArchetypeAccess<'a>) -> DynamicArchetypeRefs<'a> {
+        let storage = archetype.storage();
+        let components = storage.archetype().components();
+
+        let columns = self
+            .components
+            .iter()
+            .map(|access| {
+                let index = match archetype.component_index(access.id) {
+                    Some(index) => index,
+                    None => panic!("Archetype missing components of requested type"),
+                };
+                let info = &components[index].info;
+
+                match archetype.borrow_dyn(access.id, access.kind) {
+                    Some(access) => (info, access),
+                    None => panic!(
+                        "Access to components of type `{}` is not granted",
+                        info.name(),
+                    ),
+                }
+            })
+            .collect();
+
+        DynamicArchetypeRefs { columns }
+    }
+}
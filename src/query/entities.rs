@@ -0,0 +1,115 @@
+use {
+    super::{
+        access::{AccessComponent, AccessOne, ArchetypeAccess},
+        view::{ArchetypeRefs, ChunkRefs, View},
+    },
+    crate::{
+        archetype::{Archetype, EntityIndex},
+        entity::{Entity, EntityLocations},
+    },
+    core::ptr::NonNull,
+};
+
+/// View that yields `Entity` handles.
+/// Matches all archetypes and does not borrow components.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Entities;
+
+pub struct ChunkEntities<'a> {
+    ptr: NonNull<EntityIndex>,
+    entities: &'a EntityLocations,
+}
+
+// Behaves like `EntitySlice<'a>`.
+unsafe impl<'a> Send for ChunkEntities<'a> {}
+
+impl<'a> ChunkRefs for ChunkEntities<'a> {
+    type Item = Entity;
+    type Slice = EntitySlice<'a>;
+
+    unsafe fn next(&mut self) -> Entity {
+        let index = self.ptr.as_ref().0;
+        self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(1));
+        self.entities.get_entity(index)
+    }
+
+    unsafe fn slice(self, len: usize) -> EntitySlice<'a> {
+        EntitySlice {
+            indices: core::slice::from_raw_parts(self.ptr.as_ptr(), len),
+            entities: self.entities,
+        }
+    }
+
+    unsafe fn skip(&mut self, count: usize) {
+        self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(count));
+    }
+}
+
+/// Entities of one chunk.
+#[derive(Clone, Copy)]
+pub struct EntitySlice<'a> {
+    indices: &'a [EntityIndex],
+    entities: &'a EntityLocations,
+}
+
+impl<'a> EntitySlice<'a> {
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Returns entity at specified position in the chunk.
+    pub fn get(&self, index: usize) -> Option<Entity> {
+        let index = self.indices.get(index)?;
+        Some(self.entities.get_entity(index.0))
+    }
+
+    /// Returns iterator over entities of the chunk.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Entity> + 'a {
+        let entities = self.entities;
+        self.indices
+            .iter()
+            .map(move |index| entities.get_entity(index.0))
+    }
+}
+
+pub struct ArchetypeEntities<'a> {
+    entities: &'a EntityLocations,
+}
+
+impl<'a> ArchetypeRefs for ArchetypeEntities<'a> {
+    type Item = ChunkEntities<'a>;
+    unsafe fn get(&self, base: NonNull<u8>) -> ChunkEntities<'a> {
+        // Entity indices are stored at the beginning of the chunk.
+        ChunkEntities {
+            ptr: base.cast(),
+            entities: self.entities,
+        }
+    }
+}
+
+impl AccessOne for Entities {
+    fn access(&self, _: &Archetype) -> Option<AccessComponent> {
+        None
+    }
+
+    fn matches(&self, _: &Archetype) -> bool {
+        true
+    }
+}
+
+impl<'a> View<'a> for Entities {
+    type EntityView = Entity;
+    type ChunkView = EntitySlice<'a>;
+    type ChunkRefs = ChunkEntities<'a>;
+    type ArchetypeRefs = ArchetypeEntities<'a>;
+
+    fn acquire(&self, archetype: ArchetypeAccess<'a>) -> ArchetypeEntities<'a> {
+        ArchetypeEntities {
+            entities: archetype.entities(),
+        }
+    }
+}
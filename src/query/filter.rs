@@ -0,0 +1,85 @@
+use {
+    super::{
+        access::{AccessComponent, AccessOne, ArchetypeAccess},
+        view::View,
+    },
+    crate::archetype::Archetype,
+    core::{any::TypeId, marker::PhantomData},
+};
+
+/// Filter that matches archetypes with components of type `T`.
+/// Does not borrow components.
+pub struct With<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+pub fn with<T>() -> With<T> {
+    With {
+        marker: PhantomData,
+    }
+}
+
+impl<T> Default for With<T> {
+    fn default() -> Self {
+        with()
+    }
+}
+
+impl<T: 'static> AccessOne for With<T> {
+    fn access(&self, _: &Archetype) -> Option<AccessComponent> {
+        None
+    }
+
+    fn matches(&self, archetype: &Archetype) -> bool {
+        archetype.contains(TypeId::of::<T>())
+    }
+
+    fn required(&self) -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+}
+
+impl<'a, T: 'static> View<'a> for With<T> {
+    type EntityView = ();
+    type ChunkView = ();
+    type ChunkRefs = ();
+    type ArchetypeRefs = ();
+
+    fn acquire(&self, _: ArchetypeAccess<'a>) {}
+}
+
+/// Filter that matches archetypes without components of type `T`.
+pub struct Without<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+pub fn without<T>() -> Without<T> {
+    Without {
+        marker: PhantomData,
+    }
+}
+
+impl<T> Default for Without<T> {
+    fn default() -> Self {
+        without()
+    }
+}
+
+impl<T: 'static> AccessOne for Without<T> {
+    fn access(&self, _: &Archetype) -> Option<AccessComponent> {
+        None
+    }
+
+    fn matches(&self, archetype: &Archetype) -> bool {
+        !archetype.contains(TypeId::of::<T>())
+    }
+}
+
+impl<'a, T: 'static> View<'a> for Without<T> {
+    type EntityView = ();
+    type ChunkView = ();
+    type ChunkRefs = ();
+    type ArchetypeRefs = ();
+
+    fn acquire(&self, _: ArchetypeAccess<'a>) {}
+}
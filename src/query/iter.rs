@@ -1,7 +1,7 @@
 use {
     super::{
-        access::ArchetypeRef,
-        view::{ArchetypeRefs, ChunkRefs},
+        access::{archetype_matches, assert_no_conflicts, Access, ArchetypeAccess, ArchetypeRef},
+        view::{ArchetypeRefs, ChunkRefs, View},
     },
     core::{cmp::min, marker::PhantomData, ptr::NonNull, slice},
 };
@@ -18,13 +18,16 @@ where
     type Item = T::Item;
 
     fn next(&mut self) -> Option<T::Item> {
-        if self.len > 0 {
-            let result = unsafe { self.ptrs.next() };
+        while self.len > 0 {
             self.len -= 1;
-            Some(result)
-        } else {
-            None
+
+            if unsafe { self.ptrs.matches() } {
+                return Some(unsafe { self.ptrs.next() });
+            }
+
+            unsafe { self.ptrs.skip(1) };
         }
+        None
     }
 }
 
@@ -42,21 +45,179 @@ where
     type Item = ChunkEntityIter<A::Item>;
 
     fn next(&mut self) -> Option<ChunkEntityIter<A::Item>> {
-        let raw_chunk = *self.raw_chunks.next()?;
+        while self.len > 0 {
+            let raw_chunk = *self.raw_chunks.next()?;
+
+            let len = min(self.len, self.chunk_capacity);
+            self.len -= len;
+
+            if unsafe { self.refs.matches(raw_chunk) } {
+                let ptrs = unsafe { self.refs.get(raw_chunk) };
+                return Some(ChunkEntityIter { ptrs, len });
+            }
+        }
+        None
+    }
+}
+
+pub struct ArchetypeChunkIter<'a, A> {
+    pub(crate) raw_chunks: slice::Iter<'a, NonNull<u8>>,
+    pub(crate) len: usize,
+    pub(crate) chunk_capacity: usize,
+    pub(crate) refs: A,
+}
+
+impl<'a, A> Iterator for ArchetypeChunkIter<'a, A>
+where
+    A: ArchetypeRefs,
+{
+    type Item = <A::Item as ChunkRefs>::Slice;
+
+    fn next(&mut self) -> Option<<A::Item as ChunkRefs>::Slice> {
+        while self.len > 0 {
+            let raw_chunk = *self.raw_chunks.next()?;
+
+            let len = min(self.len, self.chunk_capacity);
+            self.len -= len;
+
+            if unsafe { self.refs.matches(raw_chunk) } {
+                return Some(unsafe { self.refs.get(raw_chunk).slice(len) });
+            }
+        }
+        None
+    }
+}
+
+/// Returns iterator over views of all entities in archetypes that match the view.
+pub(crate) fn query_archetypes<'a, V, I>(
+    archetypes: I,
+    view: V,
+) -> impl Iterator<Item = <V as View<'a>>::EntityView> + 'a
+where
+    V: View<'a> + Access + 'a,
+    I: Iterator<Item = ArchetypeAccess<'a>> + 'a,
+{
+    archetypes
+        .filter_map(move |archetype| {
+            let storage = archetype.storage();
+
+            if storage.len() == 0 || !archetype_matches(&view, storage.archetype()) {
+                return None;
+            }
+
+            assert_no_conflicts(&view, storage.archetype());
+
+            let iter = ArchetypeEntityIter {
+                raw_chunks: storage.raw_chunks().iter(),
+                len: storage.len(),
+                chunk_capacity: storage.chunk_capacity(),
+                refs: view.acquire(archetype),
+            };
+
+            Some(iter.flatten())
+        })
+        .flatten()
+}
 
-        let len = min(self.len, self.chunk_capacity);
-        self.len -= len;
+/// Returns iterator over views of all chunks in archetypes that match the view.
+/// Each chunk view covers all entities in the chunk.
+pub(crate) fn query_archetype_chunks<'a, V, I>(
+    archetypes: I,
+    view: V,
+) -> impl Iterator<Item = <V as View<'a>>::ChunkView> + 'a
+where
+    V: View<'a> + Access + 'a,
+    I: Iterator<Item = ArchetypeAccess<'a>> + 'a,
+{
+    archetypes
+        .filter_map(move |archetype| {
+            let storage = archetype.storage();
+
+            if storage.len() == 0 || !archetype_matches(&view, storage.archetype()) {
+                return None;
+            }
+
+            assert_no_conflicts(&view, storage.archetype());
+
+            Some(ArchetypeChunkIter {
+                raw_chunks: storage.raw_chunks().iter(),
+                len: storage.len(),
+                chunk_capacity: storage.chunk_capacity(),
+                refs: view.acquire(archetype),
+            })
+        })
+        .flatten()
+}
+
+/// Checks that entity in archetype passes row filters of the view.
+///
+/// # Safety
+///
+/// `index` must be less than number of entities in the archetype.
+pub(crate) unsafe fn matches_one<'a, V>(
+    view: &V,
+    archetype: ArchetypeAccess<'a>,
+    index: usize,
+) -> bool
+where
+    V: View<'a> + Access,
+{
+    let storage = archetype.storage();
+    debug_assert!(index < storage.len());
+    assert_no_conflicts(view, storage.archetype());
+
+    let chunk_capacity = storage.chunk_capacity();
+    let raw_chunk = *storage.raw_chunks().get_unchecked(index / chunk_capacity);
 
-        let ptrs = unsafe { self.refs.get(raw_chunk) };
-        Some(ChunkEntityIter { ptrs, len })
+    let refs = view.acquire(archetype);
+    if !refs.matches(raw_chunk) {
+        return false;
     }
+
+    let mut chunk = refs.get(raw_chunk);
+    chunk.skip(index % chunk_capacity);
+    chunk.matches()
+}
+
+/// Returns view of one entity in archetype.
+/// Row filters are not checked.
+///
+/// # Safety
+///
+/// `index` must be less than number of entities in the archetype.
+///
+/// # Panics
+///
+/// This function panics if view borrows same components in conflicting ways.
+pub(crate) unsafe fn fetch_one<'a, V>(
+    view: &V,
+    archetype: ArchetypeAccess<'a>,
+    index: usize,
+) -> <V as View<'a>>::EntityView
+where
+    V: View<'a> + Access,
+{
+    let storage = archetype.storage();
+    debug_assert!(index < storage.len());
+    assert_no_conflicts(view, storage.archetype());
+
+    let chunk_capacity = storage.chunk_capacity();
+    let raw_chunk = *storage.raw_chunks().get_unchecked(index / chunk_capacity);
+
+    let refs = view.acquire(archetype);
+    let mut chunk = refs.get(raw_chunk);
+    chunk.skip(index % chunk_capacity);
+    chunk.next()
 }
 
 macro_rules! impl_for_tuple {
     () => {
         impl ChunkRefs for () {
             type Item = ();
+            type Slice = ();
             unsafe fn next(&mut self) -> () {}
+            unsafe fn slice(self, _: usize) -> () {}
+            unsafe fn skip(&mut self, _: usize) {}
         }
 
         impl ArchetypeRefs for () {
@@ -71,11 +232,31 @@ macro_rules! impl_for_tuple {
             $($a: ChunkRefs,)+
         {
             type Item = ($($a::Item,)+);
+            type Slice = ($($a::Slice,)+);
+
             unsafe fn next(&mut self) -> ($($a::Item,)+) {
                 #![allow(non_snake_case)]
                 let ($($a,)+) = self;
                 ($($a.next(),)+)
             }
+
+            unsafe fn slice(self, len: usize) -> ($($a::Slice,)+) {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                ($($a.slice(len),)+)
+            }
+
+            unsafe fn skip(&mut self, count: usize) {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                $($a.skip(count);)+
+            }
+
+            unsafe fn matches(&self) -> bool {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                $($a.matches())&&+
+            }
         }
 
         impl<$($a),+> ArchetypeRefs for ($($a,)+)
@@ -88,6 +269,12 @@ macro_rules! impl_for_tuple {
                 let ($($a,)+) = self;
                 ($($a.get(base),)+)
             }
+
+            unsafe fn matches(&self, base: NonNull<u8>) -> bool {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                $($a.matches(base))&&+
+            }
         }
     };
 }
@@ -97,3 +284,11 @@ impl_for_tuple!(A);
 impl_for_tuple!(A, B);
 impl_for_tuple!(A, B, C);
 impl_for_tuple!(A, B, C, D);
+impl_for_tuple!(A, B, C, D, E);
+impl_for_tuple!(A, B, C, D, E, F);
+impl_for_tuple!(A, B, C, D, E, F, G);
+impl_for_tuple!(A, B, C, D, E, F, G, H);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
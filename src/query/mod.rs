@@ -1,12 +1,31 @@
 mod access;
+mod and;
+mod changed;
+mod dynamic;
+mod entities;
+mod filter;
 pub mod iter;
+mod or;
+#[cfg(feature = "parallel")]
+pub(crate) mod par;
 mod read;
 mod view;
 mod write;
 
 pub use self::{
-    access::{Access, AccessComponent, AccessKind, ArchetypeAccess, ArchetypeRef},
+    access::{
+        archetype_matches, Access, AccessComponent, AccessKind, AccessOne, ArchetypeAccess,
+        ArchetypeRef,
+    },
+    and::And,
+    changed::{added, changed, Added, Changed},
+    dynamic::{DynamicChunk, DynamicComponent, DynamicQuery, DynamicRow},
+    entities::{Entities, EntitySlice},
+    filter::{with, without, With, Without},
+    or::{AnyOf, Or},
     read::{read, Read},
     view::View,
     write::{write, Write},
 };
+
+pub(crate) use self::access::assert_no_conflicts;
@@ -0,0 +1,371 @@
+use {
+    super::{
+        access::{Access, AccessComponent, ArchetypeAccess},
+        view::{ArchetypeRefs, ChunkRefs, View},
+    },
+    crate::archetype::Archetype,
+    alloc::vec::Vec,
+    core::{any::TypeId, ptr::NonNull},
+};
+
+/// Filter that matches archetypes that match any of the filters in tuple `T`.
+/// Does not borrow components.
+/// Filters that read change ticks are checked for each chunk and entity,
+/// components they access are borrowed as well.
+///
+/// Yields tuple of flags telling which filters match the entity.
+pub struct Or<T>(pub T);
+
+/// View that matches archetypes that match any of the views in tuple `T`.
+///
+/// Yields tuple of optional views, `Some` for each view that matches the entity.
+/// Only components of matching views are borrowed.
+pub struct AnyOf<T>(pub T);
+
+/// Refs of `Or` and `AnyOf` branches.
+/// Chunk or entity matches if any of the branches does.
+pub struct AnyRefs<T>(T);
+
+/// Whether branch of `Or` matches.
+pub enum Matched<R> {
+    /// Branch doesn't read change ticks, so it matches all entities or none.
+    Fixed(bool),
+
+    /// Branch reads change ticks and is checked for each chunk and entity.
+    Ticks(R),
+}
+
+impl<C> ChunkRefs for Matched<C>
+where
+    C: ChunkRefs,
+{
+    type Item = bool;
+    type Slice = bool;
+
+    unsafe fn next(&mut self) -> bool {
+        match self {
+            Matched::Fixed(matched) => *matched,
+            Matched::Ticks(refs) => {
+                let matched = refs.matches();
+                refs.skip(1);
+                matched
+            }
+        }
+    }
+
+    unsafe fn slice(self, _: usize) -> bool {
+        match self {
+            Matched::Fixed(matched) => matched,
+            // Chunks that don't match are turned into `Fixed(false)`.
+            Matched::Ticks(_) => true,
+        }
+    }
+
+    unsafe fn skip(&mut self, count: usize) {
+        if let Matched::Ticks(refs) = self {
+            refs.skip(count);
+        }
+    }
+
+    unsafe fn matches(&self) -> bool {
+        match self {
+            Matched::Fixed(matched) => *matched,
+            Matched::Ticks(refs) => refs.matches(),
+        }
+    }
+}
+
+impl<A> ArchetypeRefs for Matched<A>
+where
+    A: ArchetypeRefs,
+{
+    type Item = Matched<A::Item>;
+
+    unsafe fn get(&self, base: NonNull<u8>) -> Matched<A::Item> {
+        match self {
+            Matched::Fixed(matched) => Matched::Fixed(*matched),
+            Matched::Ticks(refs) if refs.matches(base) => Matched::Ticks(refs.get(base)),
+            Matched::Ticks(_) => Matched::Fixed(false),
+        }
+    }
+
+    unsafe fn matches(&self, base: NonNull<u8>) -> bool {
+        match self {
+            Matched::Fixed(matched) => *matched,
+            Matched::Ticks(refs) => refs.matches(base),
+        }
+    }
+}
+
+/// Refs of `AnyOf` branch, `None` if branch doesn't match the archetype.
+pub struct Branch<R>(Option<R>);
+
+impl<C> ChunkRefs for Branch<C>
+where
+    C: ChunkRefs,
+{
+    type Item = Option<C::Item>;
+    type Slice = Option<C::Slice>;
+
+    unsafe fn next(&mut self) -> Option<C::Item> {
+        match &mut self.0 {
+            Some(refs) if refs.matches() => Some(refs.next()),
+            Some(refs) => {
+                refs.skip(1);
+                None
+            }
+            None => None,
+        }
+    }
+
+    unsafe fn slice(self, len: usize) -> Option<C::Slice> {
+        self.0.map(|refs| refs.slice(len))
+    }
+
+    unsafe fn skip(&mut self, count: usize) {
+        if let Some(refs) = &mut self.0 {
+            refs.skip(count);
+        }
+    }
+
+    unsafe fn matches(&self) -> bool {
+        self.0.as_ref().is_some_and(|refs| refs.matches())
+    }
+}
+
+impl<A> ArchetypeRefs for Branch<A>
+where
+    A: ArchetypeRefs,
+{
+    type Item = Branch<A::Item>;
+
+    unsafe fn get(&self, base: NonNull<u8>) -> Branch<A::Item> {
+        match &self.0 {
+            Some(refs) if refs.matches(base) => Branch(Some(refs.get(base))),
+            _ => Branch(None),
+        }
+    }
+
+    unsafe fn matches(&self, base: NonNull<u8>) -> bool {
+        self.0.as_ref().is_some_and(|refs| refs.matches(base))
+    }
+}
+
+/// Checks that access reads change ticks of components in the archetype.
+fn reads_ticks(access: &impl Access, archetype: &Archetype) -> bool {
+    access.with_ticks(archetype, |ids| !ids.is_empty())
+}
+
+macro_rules! impl_for_tuple {
+    ($($a:ident),+ $(,)?) => {
+        impl<$($a),+> ChunkRefs for AnyRefs<($($a,)+)>
+        where
+            $($a: ChunkRefs,)+
+        {
+            type Item = ($($a::Item,)+);
+            type Slice = ($($a::Slice,)+);
+
+            unsafe fn next(&mut self) -> ($($a::Item,)+) {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &mut self.0;
+                ($($a.next(),)+)
+            }
+
+            unsafe fn slice(self, len: usize) -> ($($a::Slice,)+) {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self.0;
+                ($($a.slice(len),)+)
+            }
+
+            unsafe fn skip(&mut self, count: usize) {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &mut self.0;
+                $($a.skip(count);)+
+            }
+
+            unsafe fn matches(&self) -> bool {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                $($a.matches())||+
+            }
+        }
+
+        impl<$($a),+> ArchetypeRefs for AnyRefs<($($a,)+)>
+        where
+            $($a: ArchetypeRefs,)+
+        {
+            type Item = AnyRefs<($($a::Item,)+)>;
+
+            unsafe fn get(&self, base: NonNull<u8>) -> Self::Item {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                AnyRefs(($($a.get(base),)+))
+            }
+
+            unsafe fn matches(&self, base: NonNull<u8>) -> bool {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                $($a.matches(base))||+
+            }
+        }
+
+        impl<$($a),+> Default for Or<($($a,)+)>
+        where
+            $($a: Default,)+
+        {
+            fn default() -> Self {
+                Or(($($a::default(),)+))
+            }
+        }
+
+        impl<$($a),+> Access for Or<($($a,)+)>
+        where
+            $($a: Access,)+
+        {
+            fn with_accesses<T>(
+                &self,
+                archetype: &Archetype,
+                f: impl FnOnce(&[AccessComponent]) -> T,
+            ) -> T {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                let mut accesses = Vec::new();
+                $(
+                    if $a.matches(archetype) && reads_ticks($a, archetype) {
+                        $a.with_accesses(archetype, |a| accesses.extend_from_slice(a));
+                    }
+                )+
+                accesses.sort_unstable_by_key(|a| a.id);
+                f(&accesses)
+            }
+
+            fn matches(&self, archetype: &Archetype) -> bool {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                $($a.matches(archetype))||+
+            }
+
+            fn with_ticks<T>(&self, archetype: &Archetype, f: impl FnOnce(&[TypeId]) -> T) -> T {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                let mut ticks = Vec::new();
+                $(
+                    if $a.matches(archetype) {
+                        $a.with_ticks(archetype, |t| ticks.extend_from_slice(t));
+                    }
+                )+
+                f(&ticks)
+            }
+        }
+
+        impl<'a $(, $a)+> View<'a> for Or<($($a,)+)>
+        where
+            $($a: View<'a> + Access,)+
+        {
+            type EntityView = ($(const_tree_for_token!($a, bool),)+);
+            type ChunkView = ($(const_tree_for_token!($a, bool),)+);
+            type ChunkRefs = AnyRefs<($(Matched<$a::ChunkRefs>,)+)>;
+            type ArchetypeRefs = AnyRefs<($(Matched<$a::ArchetypeRefs>,)+)>;
+
+            fn acquire(&self, archetype: ArchetypeAccess<'a>) -> Self::ArchetypeRefs {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                let storage = archetype.storage();
+                AnyRefs(($(
+                    if !$a.matches(storage.archetype()) {
+                        Matched::Fixed(false)
+                    } else if reads_ticks($a, storage.archetype()) {
+                        Matched::Ticks($a.acquire(archetype))
+                    } else {
+                        Matched::Fixed(true)
+                    },
+                )+))
+            }
+        }
+
+        impl<$($a),+> Default for AnyOf<($($a,)+)>
+        where
+            $($a: Default,)+
+        {
+            fn default() -> Self {
+                AnyOf(($($a::default(),)+))
+            }
+        }
+
+        impl<$($a),+> Access for AnyOf<($($a,)+)>
+        where
+            $($a: Access,)+
+        {
+            fn with_accesses<T>(
+                &self,
+                archetype: &Archetype,
+                f: impl FnOnce(&[AccessComponent]) -> T,
+            ) -> T {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                let mut accesses = Vec::new();
+                $(
+                    if $a.matches(archetype) {
+                        $a.with_accesses(archetype, |a| accesses.extend_from_slice(a));
+                    }
+                )+
+                accesses.sort_unstable_by_key(|a| a.id);
+                f(&accesses)
+            }
+
+            fn matches(&self, archetype: &Archetype) -> bool {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                $($a.matches(archetype))||+
+            }
+
+            fn with_ticks<T>(&self, archetype: &Archetype, f: impl FnOnce(&[TypeId]) -> T) -> T {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                let mut ticks = Vec::new();
+                $(
+                    if $a.matches(archetype) {
+                        $a.with_ticks(archetype, |t| ticks.extend_from_slice(t));
+                    }
+                )+
+                f(&ticks)
+            }
+        }
+
+        impl<'a $(, $a)+> View<'a> for AnyOf<($($a,)+)>
+        where
+            $($a: View<'a> + Access,)+
+        {
+            type EntityView = ($(Option<$a::EntityView>,)+);
+            type ChunkView = ($(Option<$a::ChunkView>,)+);
+            type ChunkRefs = AnyRefs<($(Branch<$a::ChunkRefs>,)+)>;
+            type ArchetypeRefs = AnyRefs<($(Branch<$a::ArchetypeRefs>,)+)>;
+
+            fn acquire(&self, archetype: ArchetypeAccess<'a>) -> Self::ArchetypeRefs {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = &self.0;
+                let storage = archetype.storage();
+                AnyRefs(($(
+                    Branch(if $a.matches(storage.archetype()) {
+                        Some($a.acquire(archetype))
+                    } else {
+                        None
+                    }),
+                )+))
+            }
+        }
+    };
+}
+
+impl_for_tuple!(A);
+impl_for_tuple!(A, B);
+impl_for_tuple!(A, B, C);
+impl_for_tuple!(A, B, C, D);
+impl_for_tuple!(A, B, C, D, E);
+impl_for_tuple!(A, B, C, D, E, F);
+impl_for_tuple!(A, B, C, D, E, F, G);
+impl_for_tuple!(A, B, C, D, E, F, G, H);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
@@ -0,0 +1,60 @@
+use {
+    super::{
+        access::{archetype_matches, assert_no_conflicts, Access, ArchetypeAccess},
+        iter::{query_archetype_chunks, ArchetypeEntityIter},
+        view::View,
+    },
+    alloc::vec::Vec,
+    rayon::prelude::*,
+};
+
+/// Returns parallel iterator over views of all chunks in archetypes that match the view.
+///
+/// Views are acquired on calling thread before any work is split,
+/// so only chunk views are sent to other threads.
+/// Chunks are yielded in the same order as by `query_archetype_chunks`.
+pub(crate) fn par_query_archetype_chunks<'a, V, I>(
+    archetypes: I,
+    view: V,
+) -> impl IndexedParallelIterator<Item = <V as View<'a>>::ChunkView> + 'a
+where
+    V: View<'a> + Access + 'a,
+    V::ChunkView: Send,
+    I: Iterator<Item = ArchetypeAccess<'a>> + 'a,
+{
+    query_archetype_chunks(archetypes, view)
+        .collect::<Vec<_>>()
+        .into_par_iter()
+}
+
+/// Calls `f` with views of all entities in archetypes that match the view.
+/// Chunks are distributed across rayon's thread pool.
+pub(crate) fn par_for_each_archetypes<'a, V, I, F>(archetypes: I, view: V, f: F)
+where
+    V: View<'a> + Access + 'a,
+    V::ChunkRefs: Send,
+    I: Iterator<Item = ArchetypeAccess<'a>> + 'a,
+    F: Fn(<V as View<'a>>::EntityView) + Sync,
+{
+    let chunks: Vec<_> = archetypes
+        .filter_map(|archetype| {
+            let storage = archetype.storage();
+
+            if storage.len() == 0 || !archetype_matches(&view, storage.archetype()) {
+                return None;
+            }
+
+            assert_no_conflicts(&view, storage.archetype());
+
+            Some(ArchetypeEntityIter {
+                raw_chunks: storage.raw_chunks().iter(),
+                len: storage.len(),
+                chunk_capacity: storage.chunk_capacity(),
+                refs: view.acquire(archetype),
+            })
+        })
+        .flatten()
+        .collect();
+
+    chunks.into_par_iter().for_each(|chunk| chunk.for_each(&f));
+}
@@ -1,9 +1,13 @@
 use {
     super::{
-        access::{ArchetypeAccess, ArchetypeRef},
+        access::{AccessComponent, AccessKind, AccessOne, ArchetypeAccess, ArchetypeRef},
         view::{ChunkRef, View},
     },
-    core::{any::type_name, marker::PhantomData},
+    crate::archetype::Archetype,
+    core::{
+        any::{type_name, TypeId},
+        marker::PhantomData,
+    },
 };
 
 pub struct Read<T> {
@@ -16,8 +20,28 @@ pub fn read<T>() -> Read<T> {
     }
 }
 
+impl<T> Default for Read<T> {
+    fn default() -> Self {
+        read()
+    }
+}
+
+impl<T: 'static> AccessOne for Read<T> {
+    fn access(&self, _: &Archetype) -> Option<AccessComponent> {
+        Some(AccessComponent {
+            id: TypeId::of::<T>(),
+            kind: AccessKind::Shared,
+        })
+    }
+
+    fn required(&self) -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+}
+
 impl<'a, T: 'static> View<'a> for Read<T> {
     type EntityView = &'a T;
+    type ChunkView = &'a [T];
     type ChunkRefs = ChunkRef<'a, T>;
     type ArchetypeRefs = ArchetypeRef<'a, T>;
 
@@ -31,3 +55,44 @@ impl<'a, T: 'static> View<'a> for Read<T> {
         }
     }
 }
+
+/// Optional access to components of type `T`.
+/// Matches all archetypes and yields `None` for entities without the component.
+impl<T: 'static> AccessOne for Option<Read<T>> {
+    fn access(&self, archetype: &Archetype) -> Option<AccessComponent> {
+        let id = TypeId::of::<T>();
+        if archetype.contains(id) {
+            Some(AccessComponent {
+                id,
+                kind: AccessKind::Shared,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn matches(&self, _: &Archetype) -> bool {
+        true
+    }
+}
+
+impl<'a, T: 'static> View<'a> for Option<Read<T>> {
+    type EntityView = Option<&'a T>;
+    type ChunkView = Option<&'a [T]>;
+    type ChunkRefs = Option<ChunkRef<'a, T>>;
+    type ArchetypeRefs = Option<ArchetypeRef<'a, T>>;
+
+    fn acquire(&self, archetype: ArchetypeAccess<'a>) -> Option<ArchetypeRef<'a, T>> {
+        if !archetype.storage().archetype().contains(TypeId::of::<T>()) {
+            return None;
+        }
+
+        match archetype.borrow_ref() {
+            Some(access) => Some(access),
+            None => panic!(
+                "Access to components of type `{}` is not granted",
+                type_name::<T>(),
+            ),
+        }
+    }
+}
@@ -1,11 +1,27 @@
 use {
-    super::access::{ArchetypeAccess, ArchetypeRef, ArchetypeRefMut},
+    super::access::{ArchetypeAccess, ArchetypeRef, ArchetypeRefMut, ChangeTicks},
     core::{marker::PhantomData, ptr::NonNull},
 };
 
 pub trait ChunkRefs {
     type Item;
+
+    /// Slices of components of the chunk.
+    type Slice;
+
     unsafe fn next(&mut self) -> Self::Item;
+
+    /// Returns slices of `len` items.
+    unsafe fn slice(self, len: usize) -> Self::Slice;
+
+    /// Skips `count` items.
+    unsafe fn skip(&mut self, count: usize);
+
+    /// Checks that next item passes row filters.
+    /// Items that don't are skipped by entity iterators.
+    unsafe fn matches(&self) -> bool {
+        true
+    }
 }
 
 #[repr(transparent)]
@@ -14,33 +30,74 @@ pub struct ChunkRef<'a, T> {
     marker: PhantomData<&'a [T]>,
 }
 
+// Behaves like `&'a [T]`.
+unsafe impl<'a, T: Sync> Send for ChunkRef<'a, T> {}
+
 impl<'a, T> ChunkRefs for ChunkRef<'a, T> {
     type Item = &'a T;
+    type Slice = &'a [T];
     unsafe fn next(&mut self) -> &'a T {
         let result = &*self.ptr.as_ptr();
         self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(1));
         result
     }
+
+    unsafe fn slice(self, len: usize) -> &'a [T] {
+        core::slice::from_raw_parts(self.ptr.as_ptr(), len)
+    }
+
+    unsafe fn skip(&mut self, count: usize) {
+        self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(count));
+    }
 }
 
-#[repr(transparent)]
 pub struct ChunkRefMut<'a, T> {
     ptr: NonNull<T>,
+
+    /// Zero-sized components have no change ticks.
+    ticks: Option<ChangeTicks>,
     marker: PhantomData<&'a mut [T]>,
 }
 
+// Behaves like `&'a mut [T]`.
+unsafe impl<'a, T: Send> Send for ChunkRefMut<'a, T> {}
+
 impl<'a, T> ChunkRefs for ChunkRefMut<'a, T> {
     type Item = &'a mut T;
+    type Slice = &'a mut [T];
     unsafe fn next(&mut self) -> &'a mut T {
         let result = &mut *self.ptr.as_ptr();
-        self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(1));
+        if let Some(ticks) = &self.ticks {
+            ticks.mark(1);
+        }
+        self.skip(1);
         result
     }
+
+    unsafe fn slice(self, len: usize) -> &'a mut [T] {
+        if let Some(ticks) = &self.ticks {
+            ticks.mark(len);
+        }
+        core::slice::from_raw_parts_mut(self.ptr.as_ptr(), len)
+    }
+
+    unsafe fn skip(&mut self, count: usize) {
+        self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(count));
+        if let Some(ticks) = &mut self.ticks {
+            ticks.skip(count);
+        }
+    }
 }
 
 pub trait ArchetypeRefs {
     type Item: ChunkRefs;
     unsafe fn get(&self, base: NonNull<u8>) -> Self::Item;
+
+    /// Checks that chunk may contain items that pass row filters.
+    /// Chunks that don't are skipped by iterators.
+    unsafe fn matches(&self, _base: NonNull<u8>) -> bool {
+        true
+    }
 }
 
 impl<'a, T> ArchetypeRefs for ArchetypeRef<'a, T> {
@@ -58,17 +115,60 @@ impl<'a, T> ArchetypeRefs for ArchetypeRefMut<'a, T> {
     unsafe fn get(&self, base: NonNull<u8>) -> ChunkRefMut<'a, T> {
         ChunkRefMut {
             ptr: self.get(base),
+            ticks: self.change_ticks(base),
             marker: PhantomData,
         }
     }
 }
 
+impl<C> ChunkRefs for Option<C>
+where
+    C: ChunkRefs,
+{
+    type Item = Option<C::Item>;
+    type Slice = Option<C::Slice>;
+    unsafe fn next(&mut self) -> Option<C::Item> {
+        self.as_mut().map(|refs| refs.next())
+    }
+
+    unsafe fn slice(self, len: usize) -> Option<C::Slice> {
+        self.map(|refs| refs.slice(len))
+    }
+
+    unsafe fn skip(&mut self, count: usize) {
+        if let Some(refs) = self {
+            refs.skip(count)
+        }
+    }
+
+    unsafe fn matches(&self) -> bool {
+        self.as_ref().is_none_or(|refs| refs.matches())
+    }
+}
+
+impl<A> ArchetypeRefs for Option<A>
+where
+    A: ArchetypeRefs,
+{
+    type Item = Option<A::Item>;
+    unsafe fn get(&self, base: NonNull<u8>) -> Option<A::Item> {
+        self.as_ref().map(|refs| refs.get(base))
+    }
+
+    unsafe fn matches(&self, base: NonNull<u8>) -> bool {
+        self.as_ref().is_none_or(|refs| refs.matches(base))
+    }
+}
+
 /// View components of entities in archetype.
 pub trait View<'a> {
     /// View of one entity.
     type EntityView: 'a;
 
-    type ChunkRefs: ChunkRefs<Item = Self::EntityView>;
+    /// View of all entities in one chunk.
+    type ChunkView: 'a;
+
+    type ChunkRefs: ChunkRefs<Item = Self::EntityView, Slice = Self::ChunkView>;
 
     /// View of one archetype.
     type ArchetypeRefs: ArchetypeRefs<Item = Self::ChunkRefs>;
@@ -80,3 +180,48 @@ pub trait View<'a> {
     /// This function may panic if archetype does not match `View`'s requirements.
     fn acquire(&self, archetype: ArchetypeAccess<'a>) -> Self::ArchetypeRefs;
 }
+
+macro_rules! impl_for_tuple {
+    () => {
+        impl<'a> View<'a> for () {
+            type EntityView = ();
+            type ChunkView = ();
+            type ChunkRefs = ();
+            type ArchetypeRefs = ();
+
+            fn acquire(&self, _: ArchetypeAccess<'a>) {}
+        }
+    };
+
+    ($($a:ident),+ $(,)?) => {
+        impl<'a $(, $a)+> View<'a> for ($($a,)+)
+        where
+            $($a: View<'a>,)+
+        {
+            type EntityView = ($($a::EntityView,)+);
+            type ChunkView = ($($a::ChunkView,)+);
+            type ChunkRefs = ($($a::ChunkRefs,)+);
+            type ArchetypeRefs = ($($a::ArchetypeRefs,)+);
+
+            fn acquire(&self, archetype: ArchetypeAccess<'a>) -> ($($a::ArchetypeRefs,)+) {
+                #![allow(non_snake_case)]
+                let ($($a,)+) = self;
+                ($($a.acquire(archetype),)+)
+            }
+        }
+    };
+}
+
+impl_for_tuple!();
+impl_for_tuple!(A);
+impl_for_tuple!(A, B);
+impl_for_tuple!(A, B, C);
+impl_for_tuple!(A, B, C, D);
+impl_for_tuple!(A, B, C, D, E);
+impl_for_tuple!(A, B, C, D, E, F);
+impl_for_tuple!(A, B, C, D, E, F, G);
+impl_for_tuple!(A, B, C, D, E, F, G, H);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
@@ -1,9 +1,13 @@
 use {
     super::{
-        access::{ArchetypeAccess, ArchetypeRefMut},
+        access::{AccessComponent, AccessKind, AccessOne, ArchetypeAccess, ArchetypeRefMut},
         view::{ChunkRefMut, View},
     },
-    core::{any::type_name, marker::PhantomData},
+    crate::archetype::Archetype,
+    core::{
+        any::{type_name, TypeId},
+        marker::PhantomData,
+    },
 };
 
 pub struct Write<T> {
@@ -16,8 +20,28 @@ pub fn write<T>() -> Write<T> {
     }
 }
 
+impl<T> Default for Write<T> {
+    fn default() -> Self {
+        write()
+    }
+}
+
+impl<T: 'static> AccessOne for Write<T> {
+    fn access(&self, _: &Archetype) -> Option<AccessComponent> {
+        Some(AccessComponent {
+            id: TypeId::of::<T>(),
+            kind: AccessKind::Mutable,
+        })
+    }
+
+    fn required(&self) -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+}
+
 impl<'a, T: 'static> View<'a> for Write<T> {
     type EntityView = &'a mut T;
+    type ChunkView = &'a mut [T];
     type ChunkRefs = ChunkRefMut<'a, T>;
     type ArchetypeRefs = ArchetypeRefMut<'a, T>;
 
@@ -31,3 +55,44 @@ impl<'a, T: 'static> View<'a> for Write<T> {
         }
     }
 }
+
+/// Optional access to components of type `T`.
+/// Matches all archetypes and yields `None` for entities without the component.
+impl<T: 'static> AccessOne for Option<Write<T>> {
+    fn access(&self, archetype: &Archetype) -> Option<AccessComponent> {
+        let id = TypeId::of::<T>();
+        if archetype.contains(id) {
+            Some(AccessComponent {
+                id,
+                kind: AccessKind::Mutable,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn matches(&self, _: &Archetype) -> bool {
+        true
+    }
+}
+
+impl<'a, T: 'static> View<'a> for Option<Write<T>> {
+    type EntityView = Option<&'a mut T>;
+    type ChunkView = Option<&'a mut [T]>;
+    type ChunkRefs = Option<ChunkRefMut<'a, T>>;
+    type ArchetypeRefs = Option<ArchetypeRefMut<'a, T>>;
+
+    fn acquire(&self, archetype: ArchetypeAccess<'a>) -> Option<ArchetypeRefMut<'a, T>> {
+        if !archetype.storage().archetype().contains(TypeId::of::<T>()) {
+            return None;
+        }
+
+        match archetype.borrow_mut() {
+            Some(access) => Some(access),
+            None => panic!(
+                "Access to components of type `{}` is not granted",
+                type_name::<T>(),
+            ),
+        }
+    }
+}
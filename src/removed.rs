@@ -0,0 +1,94 @@
+use {
+    crate::{entity::Entity, world::World},
+    alloc::collections::VecDeque,
+    core::{any::TypeId, marker::PhantomData},
+};
+
+/// Log of entities that lost components of one type.
+#[derive(Default)]
+pub(crate) struct RemovedLog {
+    /// Sequence number of the front entry.
+    start: u64,
+
+    /// Removed entities with tick of removal.
+    entries: VecDeque<(u64, Entity)>,
+}
+
+impl RemovedLog {
+    pub fn push(&mut self, tick: u64, entity: Entity) {
+        self.entries.push_back((tick, entity));
+    }
+
+    /// Drops entries recorded before specified tick.
+    pub fn prune(&mut self, before: u64) {
+        while let Some(&(tick, _)) = self.entries.front() {
+            if tick >= before {
+                break;
+            }
+            self.entries.pop_front();
+            self.start += 1;
+        }
+    }
+}
+
+/// Reader of entities that lost components of type `T`,
+/// either with `World::remove` or by being despawned.
+///
+/// Each reader keeps its own cursor, so any number of readers
+/// observe the same removals independently.
+/// Despawned entities are logged when `World::maintain` drops them.
+/// Entries are kept for `World::set_removed_retention` maintenance cycles,
+/// readers that fall behind miss older entries.
+pub struct RemovedComponents<T> {
+    /// Id of the world the cursor belongs to.
+    world: Option<u64>,
+
+    /// Sequence number of the next entry to read.
+    next: u64,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for RemovedComponents<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RemovedComponents<T> {
+    /// Returns new reader that starts from the oldest retained entry.
+    pub fn new() -> Self {
+        RemovedComponents {
+            world: None,
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns entities that lost components of type `T` since previous read.
+    /// Entity may be reported more than once
+    /// if it received and lost components again.
+    ///
+    /// Reading from another world resets the cursor.
+    pub fn read<'a>(&mut self, world: &'a World) -> impl Iterator<Item = Entity> + 'a
+    where
+        T: 'static,
+    {
+        if self.world != Some(world.id()) {
+            self.world = Some(world.id());
+            self.next = 0;
+        }
+
+        let log = world
+            .component_id(TypeId::of::<T>())
+            .and_then(|id| world.removed_log(id));
+
+        let entries = log.map(|log| {
+            let len = log.entries.len();
+            let skip = (self.next.saturating_sub(log.start) as usize).min(len);
+            self.next = log.start + len as u64;
+            log.entries.range(skip..)
+        });
+
+        entries.into_iter().flatten().map(|&(_, entity)| entity)
+    }
+}
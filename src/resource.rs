@@ -0,0 +1,119 @@
+use {
+    crate::{
+        archetype::Archetype,
+        component::Component,
+        query::{AccessComponent, AccessKind, AccessOne},
+        util::AsyncLock,
+    },
+    alloc::boxed::Box,
+    core::{
+        any::{Any, TypeId},
+        cell::UnsafeCell,
+        marker::PhantomData,
+    },
+};
+
+/// Resource value with its lock.
+pub(crate) struct Resource {
+    value: UnsafeCell<Box<dyn Any + Send + Sync>>,
+    lock: AsyncLock,
+}
+
+// Value is accessed through shared borrow only under the lock.
+unsafe impl Sync for Resource {}
+
+impl Resource {
+    pub fn new<T: Component>(value: T) -> Self {
+        Resource {
+            value: UnsafeCell::new(Box::new(value)),
+            lock: AsyncLock::new(),
+        }
+    }
+
+    pub fn lock(&self) -> &AsyncLock {
+        &self.lock
+    }
+
+    /// # Safety
+    ///
+    /// Resource must be locked by the caller.
+    pub unsafe fn get_unchecked<T: 'static>(&self) -> Option<&T> {
+        (**self.value.get()).downcast_ref()
+    }
+
+    /// Returns cell with the value.
+    /// Value can be borrowed mutably only under mutable lock.
+    pub fn cell(&self) -> &UnsafeCell<Box<dyn Any + Send + Sync>> {
+        &self.value
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.value.get_mut().downcast_mut()
+    }
+
+    pub fn into_inner<T: 'static>(self) -> Option<T> {
+        self.value.into_inner().downcast().ok().map(|value| *value)
+    }
+}
+
+/// Shared access to resource of type `T`.
+/// Locks the resource along with components when used with `World::lock`.
+pub struct ReadRes<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+pub fn read_res<T>() -> ReadRes<T> {
+    ReadRes {
+        marker: PhantomData,
+    }
+}
+
+impl<T> Default for ReadRes<T> {
+    fn default() -> Self {
+        read_res()
+    }
+}
+
+impl<T: 'static> AccessOne for ReadRes<T> {
+    fn access(&self, _: &Archetype) -> Option<AccessComponent> {
+        None
+    }
+
+    fn resource(&self) -> Option<AccessComponent> {
+        Some(AccessComponent {
+            id: TypeId::of::<T>(),
+            kind: AccessKind::Shared,
+        })
+    }
+}
+
+/// Mutable access to resource of type `T`.
+/// Locks the resource along with components when used with `World::lock`.
+pub struct WriteRes<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+pub fn write_res<T>() -> WriteRes<T> {
+    WriteRes {
+        marker: PhantomData,
+    }
+}
+
+impl<T> Default for WriteRes<T> {
+    fn default() -> Self {
+        write_res()
+    }
+}
+
+impl<T: 'static> AccessOne for WriteRes<T> {
+    fn access(&self, _: &Archetype) -> Option<AccessComponent> {
+        None
+    }
+
+    fn resource(&self) -> Option<AccessComponent> {
+        Some(AccessComponent {
+            id: TypeId::of::<T>(),
+            kind: AccessKind::Mutable,
+        })
+    }
+}
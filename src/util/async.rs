@@ -1,22 +1,48 @@
 use {
-    super::mutex::Mutex,
+    super::Mutex,
     alloc::collections::VecDeque,
     core::{
         future::Future,
         pin::Pin,
-        sync::atomic::{AtomicI64, Ordering::*},
+        sync::atomic::{AtomicI64, AtomicUsize, Ordering::*},
         task::{Context, Poll, Waker},
     },
 };
 
+/// State value when mutable lock is held.
+const MUTABLE: i64 = -1;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum Kind {
     Shared,
     Mutable,
 }
 
+struct Waiter {
+    id: u64,
+    kind: Kind,
+    waker: Waker,
+    admitted: bool,
+}
+
+struct WaitQueue {
+    next_id: u64,
+    waiters: VecDeque<Waiter>,
+}
+
+/// Asynchronous readers-writer lock.
+///
+/// Lock is fair. Waiters acquire lock in order of registration,
+/// adjacent shared waiters acquire lock together.
+/// New lock requests do not overtake registered waiters.
 pub struct AsyncLock {
+    /// Number of shared locks held if non-negative.
+    /// `MUTABLE` if mutable lock is held.
     state: AtomicI64,
-    wakers: Mutex<VecDeque<(Waker, Kind)>>,
+
+    /// Number of registered waiters.
+    queued: AtomicUsize,
+    queue: Mutex<WaitQueue>,
 }
 
 pub struct SharedGuard<'a> {
@@ -25,14 +51,7 @@ pub struct SharedGuard<'a> {
 
 impl<'a> Drop for SharedGuard<'a> {
     fn drop(&mut self) {
-        let state = self.lock.state.fetch_sub(1, Release);
-        debug_assert!(state > 0);
-        if state == 1 {
-            let mut guard = self.lock.wakers.lock();
-            while let Some((waker, kind)) = guard.pop_front() {
-                waker.wake();
-            }
-        }
+        unsafe { self.lock.release_shared() }
     }
 }
 
@@ -42,12 +61,13 @@ pub struct MutableGuard<'a> {
 
 impl<'a> Drop for MutableGuard<'a> {
     fn drop(&mut self) {
-        debug_assert!(self.lock.state.load(Relaxed) < 0);
-        self.lock.state.store(0, Release);
-        let mut guard = self.lock.wakers.lock();
-        while let Some((waker, kind)) = guard.pop_front() {
-            waker.wake();
-        }
+        unsafe { self.lock.release_mutable() }
+    }
+}
+
+impl Default for AsyncLock {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -55,76 +75,290 @@ impl AsyncLock {
     pub fn new() -> Self {
         AsyncLock {
             state: AtomicI64::new(0),
-            wakers: Mutex::new(VecDeque::new()),
+            queued: AtomicUsize::new(0),
+            queue: Mutex::new(WaitQueue {
+                next_id: 0,
+                waiters: VecDeque::new(),
+            }),
         }
     }
 
+    /// Attempts to acquire shared lock.
+    /// Fails if lock is held mutably or there are registered waiters.
     pub fn try_lock_shared(&self) -> Option<SharedGuard<'_>> {
-        let state = self.state.fetch_add(1, Acquire);
-        if state >= 0 {
-            Some(SharedGuard { lock: self })
+        if self.queued.load(Acquire) == 0 {
+            self.acquire_shared()
         } else {
             None
         }
     }
 
+    /// Attempts to acquire mutable lock.
+    /// Fails if lock is held or there are registered waiters.
     pub fn try_lock_mutable(&self) -> Option<MutableGuard<'_>> {
-        if self
-            .state
-            .compare_exchange(0, i64::MIN, Acquire, Relaxed)
-            .is_ok()
-        {
-            Some(MutableGuard { lock: self })
+        if self.queued.load(Acquire) == 0 {
+            self.acquire_mutable()
         } else {
             None
         }
     }
 
+    /// Attempts to acquire shared lock.
+    /// If lock is not available, registers waiter that will be woken
+    /// when lock can be acquired.
+    ///
+    /// `waiter` holds registration between calls.
+    /// Registration is removed when lock is acquired.
+    /// If caller gives up waiting, it must call `AsyncLock::cancel`.
+    pub fn poll_lock_shared(
+        &self,
+        ctx: &mut Context,
+        waiter: &mut Option<u64>,
+    ) -> Poll<SharedGuard<'_>> {
+        if waiter.is_none() {
+            if let Some(guard) = self.try_lock_shared() {
+                return Poll::Ready(guard);
+            }
+        }
+
+        match self.poll_waiter(ctx, waiter, Kind::Shared, AsyncLock::acquire_shared) {
+            Some(guard) => Poll::Ready(guard),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Attempts to acquire mutable lock.
+    /// If lock is not available, registers waiter that will be woken
+    /// when lock can be acquired.
+    ///
+    /// `waiter` holds registration between calls.
+    /// Registration is removed when lock is acquired.
+    /// If caller gives up waiting, it must call `AsyncLock::cancel`.
+    pub fn poll_lock_mutable(
+        &self,
+        ctx: &mut Context,
+        waiter: &mut Option<u64>,
+    ) -> Poll<MutableGuard<'_>> {
+        if waiter.is_none() {
+            if let Some(guard) = self.try_lock_mutable() {
+                return Poll::Ready(guard);
+            }
+        }
+
+        match self.poll_waiter(ctx, waiter, Kind::Mutable, AsyncLock::acquire_mutable) {
+            Some(guard) => Poll::Ready(guard),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Releases shared lock.
+    /// Allows to keep lock without holding `SharedGuard`.
+    ///
+    /// # Safety
+    ///
+    /// Shared lock must be acquired and its guard forgotten.
+    pub unsafe fn release_shared(&self) {
+        // SeqCst pairs with registration in `poll_waiter`,
+        // so either releaser sees the waiter or waiter sees released lock.
+        let state = self.state.fetch_sub(1, SeqCst);
+        debug_assert!(state > 0);
+        if state == 1 {
+            self.wake_admitted();
+        }
+    }
+
+    /// Releases mutable lock.
+    /// Allows to keep lock without holding `MutableGuard`.
+    ///
+    /// # Safety
+    ///
+    /// Mutable lock must be acquired and its guard forgotten.
+    pub unsafe fn release_mutable(&self) {
+        debug_assert_eq!(self.state.load(Relaxed), MUTABLE);
+        // See `release_shared`.
+        self.state.store(0, SeqCst);
+        self.wake_admitted();
+    }
+
+    /// Removes waiter registration.
+    pub fn cancel(&self, waiter: u64) {
+        let mut queue = self.queue.lock();
+
+        if let Some(index) = queue.waiters.iter().position(|w| w.id == waiter) {
+            queue.waiters.remove(index);
+            self.queued.fetch_sub(1, Release);
+
+            // Removed waiter may have been admitted.
+            self.admit(&mut queue);
+        }
+    }
+
     pub async fn lock_shared<'a>(&'a self) -> SharedGuard<'a> {
-        SharedLockFuture { lock: self }.await
+        SharedLockFuture {
+            lock: self,
+            waiter: None,
+        }
+        .await
     }
 
     pub async fn lock_mutable<'a>(&'a self) -> MutableGuard<'a> {
-        MutableLockFuture { lock: self }.await
+        MutableLockFuture {
+            lock: self,
+            waiter: None,
+        }
+        .await
+    }
+
+    fn acquire_shared(&self) -> Option<SharedGuard<'_>> {
+        let mut state = self.state.load(Relaxed);
+        loop {
+            if state < 0 {
+                return None;
+            }
+
+            match self
+                .state
+                .compare_exchange_weak(state, state + 1, Acquire, Relaxed)
+            {
+                Ok(_) => return Some(SharedGuard { lock: self }),
+                Err(actual) => state = actual,
+            }
+        }
+    }
+
+    fn acquire_mutable(&self) -> Option<MutableGuard<'_>> {
+        match self.state.compare_exchange(0, MUTABLE, Acquire, Relaxed) {
+            Ok(_) => Some(MutableGuard { lock: self }),
+            Err(_) => None,
+        }
+    }
+
+    /// Registers or updates waiter and acquires lock if waiter is admitted.
+    fn poll_waiter<'a, G>(
+        &'a self,
+        ctx: &mut Context,
+        waiter: &mut Option<u64>,
+        kind: Kind,
+        acquire: fn(&'a Self) -> Option<G>,
+    ) -> Option<G> {
+        let mut queue = self.queue.lock();
+
+        let index = match waiter.and_then(|id| queue.waiters.iter().position(|w| w.id == id)) {
+            Some(index) => {
+                let entry = &mut queue.waiters[index];
+                debug_assert!(entry.kind == kind);
+                if !entry.waker.will_wake(ctx.waker()) {
+                    entry.waker = ctx.waker().clone();
+                }
+                index
+            }
+            None => {
+                let id = queue.next_id;
+                queue.next_id += 1;
+                queue.waiters.push_back(Waiter {
+                    id,
+                    kind,
+                    waker: ctx.waker().clone(),
+                    admitted: false,
+                });
+                self.queued.fetch_add(1, SeqCst);
+                *waiter = Some(id);
+                queue.waiters.len() - 1
+            }
+        };
+
+        // Lock could be released before registration.
+        self.admit(&mut queue);
+
+        if queue.waiters[index].admitted {
+            if let Some(guard) = acquire(self) {
+                queue.waiters.remove(index);
+                self.queued.fetch_sub(1, Release);
+                *waiter = None;
+
+                // Following shared waiters may be admitted too.
+                self.admit(&mut queue);
+                return Some(guard);
+            }
+        }
+
+        None
+    }
+
+    /// Wakes waiters that can acquire the lock after release.
+    fn wake_admitted(&self) {
+        if self.queued.load(SeqCst) > 0 {
+            let mut queue = self.queue.lock();
+            self.admit(&mut queue);
+        }
+    }
+
+    /// Admits waiters from the front of the queue that can acquire the lock
+    /// in current state and wakes them.
+    fn admit(&self, queue: &mut WaitQueue) {
+        let state = self.state.load(SeqCst);
+
+        for (index, waiter) in queue.waiters.iter_mut().enumerate() {
+            match waiter.kind {
+                Kind::Mutable => {
+                    if index == 0 && state == 0 {
+                        waiter.admitted = true;
+                        waiter.waker.wake_by_ref();
+                    }
+                    break;
+                }
+                Kind::Shared => {
+                    if state < 0 {
+                        break;
+                    }
+                    if !waiter.admitted {
+                        waiter.admitted = true;
+                        waiter.waker.wake_by_ref();
+                    }
+                }
+            }
+        }
     }
 }
 
 struct SharedLockFuture<'a> {
     lock: &'a AsyncLock,
+    waiter: Option<u64>,
+}
+
+impl<'a> Drop for SharedLockFuture<'a> {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter {
+            self.lock.cancel(waiter);
+        }
+    }
 }
 
 impl<'a> Future for SharedLockFuture<'a> {
     type Output = SharedGuard<'a>;
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<SharedGuard<'a>> {
-        match self.lock.try_lock_shared() {
-            Some(guard) => Poll::Ready(guard),
-            None => {
-                self.lock
-                    .wakers
-                    .lock()
-                    .push_back((ctx.waker().clone(), Kind::Shared));
-                Poll::Pending
-            }
-        }
+        let me = self.get_mut();
+        me.lock.poll_lock_shared(ctx, &mut me.waiter)
     }
 }
 
 struct MutableLockFuture<'a> {
     lock: &'a AsyncLock,
+    waiter: Option<u64>,
+}
+
+impl<'a> Drop for MutableLockFuture<'a> {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter {
+            self.lock.cancel(waiter);
+        }
+    }
 }
 
 impl<'a> Future for MutableLockFuture<'a> {
     type Output = MutableGuard<'a>;
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<MutableGuard<'a>> {
-        match self.lock.try_lock_mutable() {
-            Some(guard) => Poll::Ready(guard),
-            None => {
-                self.lock
-                    .wakers
-                    .lock()
-                    .push_back((ctx.waker().clone(), Kind::Shared));
-                Poll::Pending
-            }
-        }
+        let me = self.get_mut();
+        me.lock.poll_lock_mutable(ctx, &mut me.waiter)
     }
 }
@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+
+const BITS: usize = 64;
+
+/// Set of dense indices.
+///
+/// Trailing zero words are never stored,
+/// so equal sets have equal representation and hash.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub const fn new() -> Self {
+        BitSet { words: Vec::new() }
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        let word = index / BITS;
+        if self.words.len() <= word {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (index % BITS);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if let Some(word) = self.words.get_mut(index / BITS) {
+            *word &= !(1 << (index % BITS));
+            self.trim();
+        }
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        match self.words.get(index / BITS) {
+            Some(word) => word & (1 << (index % BITS)) != 0,
+            None => false,
+        }
+    }
+
+    /// Returns number of indices in the set less than `index`
+    /// if `index` is in the set.
+    pub fn rank(&self, index: usize) -> Option<usize> {
+        let word = *self.words.get(index / BITS)?;
+        let bit = 1 << (index % BITS);
+        if word & bit == 0 {
+            return None;
+        }
+
+        let before: u32 = self.words[..index / BITS]
+            .iter()
+            .map(|word| word.count_ones())
+            .sum();
+        Some(before as usize + (word & (bit - 1)).count_ones() as usize)
+    }
+
+    /// Checks that all indices from `other` are in this set.
+    pub fn is_superset(&self, other: &BitSet) -> bool {
+        other.words.len() <= self.words.len()
+            && Iterator::zip(self.words.iter(), other.words.iter()).all(|(a, b)| a & b == *b)
+    }
+
+    /// Removes all indices keeping allocated memory.
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// Makes this set equal to `other` reusing allocated memory.
+    pub fn copy_from(&mut self, other: &BitSet) {
+        self.words.clear();
+        self.words.extend_from_slice(&other.words);
+    }
+
+    /// Returns iterator over indices in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            let mut word = word;
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(w * BITS + bit)
+            })
+        })
+    }
+
+    fn trim(&mut self) {
+        while let Some(0) = self.words.last() {
+            self.words.pop();
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod r#async;
+mod bitset;
 mod capacity_overflow;
 mod display;
 mod gen;
@@ -9,6 +10,6 @@ mod type_map;
 mod unreachable_unchecked;
 
 pub(crate) use self::{
-    capacity_overflow::*, display::*, gen::*, hash::*, mutex::Mutex, r#async::*, sync::*,
-    type_map::*, unreachable_unchecked::*,
+    bitset::BitSet, capacity_overflow::*, display::*, gen::*, hash::*, mutex::Mutex, r#async::*,
+    sync::*, type_map::*, unreachable_unchecked::*,
 };
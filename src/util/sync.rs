@@ -1,16 +1,14 @@
 use {
     crate::util::capacity_overflow,
     alloc::{
-        alloc::{alloc, handle_alloc_error},
+        alloc::{alloc, dealloc, handle_alloc_error},
         vec::Vec,
     },
     core::{
         alloc::Layout,
         marker::PhantomData,
         mem::size_of,
-        ptr::{
-            copy_nonoverlapping, drop_in_place, null_mut, read, slice_from_raw_parts_mut, write,
-        },
+        ptr::{copy_nonoverlapping, drop_in_place, read, slice_from_raw_parts_mut, write, NonNull},
         sync::atomic::{AtomicUsize, Ordering::*},
     },
 };
@@ -27,6 +25,10 @@ pub struct Queue<T, D> {
     marker: PhantomData<D>,
 }
 
+/// Values are moved in and out through shared reference.
+unsafe impl<T: Send, D> Send for Queue<T, D> {}
+unsafe impl<T: Send, D> Sync for Queue<T, D> {}
+
 pub enum TryReserveError {
     CapacityOverflow,
     AllocError { layout: Layout },
@@ -35,25 +37,29 @@ pub enum TryReserveError {
 use TryReserveError::*;
 
 impl<T, D> Queue<T, D> {
-    /// Creates new empty instance of `SyncPush`.
+    /// Creates new empty instance of `Queue`.
     pub const fn new() -> Self {
-        Queue::with_capacity(0)
-    }
-
-    /// Creates new empty instance of `SyncPush`.
-    pub const fn with_capacity(cap: usize) -> Self {
         Queue {
-            ptr: null_mut(),
+            ptr: NonNull::dangling().as_ptr(),
             len: AtomicUsize::new(0),
             cap: if size_of::<T>() == 0 {
                 core::isize::MAX as usize
             } else {
-                cap
+                0
             },
             marker: PhantomData,
         }
     }
 
+    /// Creates new empty instance of `Queue` with specified capacity.
+    pub fn with_capacity(cap: usize) -> Self {
+        let mut queue = Queue::new();
+        if queue.cap < cap {
+            queue.reserve(cap);
+        }
+        queue
+    }
+
     /// Returns capacity of the queue.
     pub fn capacity(&self) -> usize {
         self.cap
@@ -62,39 +68,40 @@ impl<T, D> Queue<T, D> {
     /// Returns number of elements.
     /// It may grow and shrink concurrently.
     pub fn len(&self) -> usize {
-        self.len.load(Relaxed)
+        self.len.load(Relaxed).min(self.cap)
     }
 
     pub fn append(&mut self, values: &mut Vec<T>) {
-        let vacant = self.cap - *self.len.get_mut();
-        if values.len() >= vacant {
-            let add = values.len() - vacant;
-            self.reserve(add);
+        let len = self.len_mut();
+        let vacant = self.cap - len;
+        if values.len() > vacant {
+            self.reserve(values.len() - vacant);
         }
 
         unsafe {
+            copy_nonoverlapping(values.as_ptr(), self.ptr.add(len), values.len());
+            *self.len.get_mut() = len + values.len();
             values.set_len(0);
-            copy_nonoverlapping(
-                values.as_ptr(),
-                self.ptr.add(*self.len.get_mut()),
-                values.len(),
-            );
         }
     }
 
     pub fn push(&mut self, value: T) {
-        if *self.len.get_mut() == self.cap {
+        let len = self.len_mut();
+        if len == self.cap {
             self.reserve(1);
         }
 
         unsafe {
-            write(self.ptr.add(*self.len.get_mut()), value);
+            write(self.ptr.add(len), value);
         }
+        *self.len.get_mut() = len + 1;
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        if *self.len.get_mut() > 0 {
-            Some(unsafe { read(self.ptr.add(*self.len.get_mut() - 1)) })
+        let len = self.len_mut();
+        if len > 0 {
+            *self.len.get_mut() = len - 1;
+            Some(unsafe { read(self.ptr.add(len - 1)) })
         } else {
             None
         }
@@ -109,13 +116,26 @@ impl<T, D> Queue<T, D> {
         }
     }
 
+    /// Returns number of elements.
+    /// Failed concurrent pushes may leave length greater than capacity.
+    fn len_mut(&mut self) -> usize {
+        let len = (*self.len.get_mut()).min(self.cap);
+        *self.len.get_mut() = len;
+        len
+    }
+
     fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
         if size_of::<T>() == 0 {
             capacity_overflow()
         }
 
+        let len = self.len_mut();
+        if self.cap - len >= additional {
+            return Ok(());
+        }
+
         let cap = self.cap.checked_mul(2).ok_or(CapacityOverflow)?;
-        let cap = cap.max(self.cap.checked_add(additional).ok_or(CapacityOverflow)?);
+        let cap = cap.max(len.checked_add(additional).ok_or(CapacityOverflow)?);
         if cap > core::isize::MAX as usize {
             return Err(CapacityOverflow);
         }
@@ -127,7 +147,8 @@ impl<T, D> Queue<T, D> {
             Err(AllocError { layout })
         } else {
             unsafe {
-                copy_nonoverlapping(self.ptr, ptr, self.cap);
+                copy_nonoverlapping(self.ptr, ptr, len);
+                self.dealloc();
             }
             self.ptr = ptr;
             self.cap = cap;
@@ -135,6 +156,13 @@ impl<T, D> Queue<T, D> {
             Ok(())
         }
     }
+
+    /// Deallocates memory without dropping elements.
+    unsafe fn dealloc(&mut self) {
+        if size_of::<T>() != 0 && self.cap != 0 {
+            dealloc(self.ptr as *mut u8, Layout::array::<T>(self.cap).unwrap());
+        }
+    }
 }
 
 impl<T> Queue<T, SyncPush> {
@@ -159,7 +187,7 @@ impl<T> Queue<T, SyncPop> {
     /// Otherwise returns `Err(value)`.
     pub fn sync_pop(&self) -> Option<T> {
         let len = self.len.fetch_sub(1, Acquire).wrapping_sub(1);
-        if len < self.cap {
+        if len >= self.cap {
             self.len.store(0, Relaxed);
             None
         } else {
@@ -170,7 +198,10 @@ impl<T> Queue<T, SyncPop> {
 
 impl<T, D> Drop for Queue<T, D> {
     fn drop(&mut self) {
-        let len = *self.len.get_mut();
-        unsafe { drop_in_place(slice_from_raw_parts_mut(self.ptr, len)) }
+        let len = self.len_mut();
+        unsafe {
+            drop_in_place(slice_from_raw_parts_mut(self.ptr, len));
+            self.dealloc();
+        }
     }
 }
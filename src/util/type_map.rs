@@ -1,6 +1,6 @@
 use {
-    crate::util::{NoOpHasher, XorHasher},
-    alloc::boxed::Box,
+    crate::util::{BitSet, NoOpHasher},
+    ahash::RandomState,
     core::{any::TypeId, hash::BuildHasherDefault},
     hashbrown::HashMap,
 };
@@ -8,5 +8,5 @@ use {
 /// HashMap that utilize the fact that `TypeId`s are already hashed.
 pub type TypeIdMap<T> = HashMap<TypeId, T, BuildHasherDefault<NoOpHasher>>;
 
-/// HashMap that utilize the fact that `TypeId`s are already hashed.
-pub type TypeIdListMap<T> = HashMap<Box<[TypeId]>, T, BuildHasherDefault<XorHasher>>;
+/// HashMap keyed by sets of component ids.
+pub type SignatureMap<T> = HashMap<BitSet, T, RandomState>;
@@ -1,10 +1,22 @@
+/// Hints that code is unreachable.
+/// Panics in debug builds.
+///
+/// # Safety
+///
+/// Must never be called.
 #[cfg(debug_assertions)]
 #[track_caller]
-pub fn unreachable_unchecked() -> ! {
+pub unsafe fn unreachable_unchecked() -> ! {
     unreachable!()
 }
 
+/// Hints that code is unreachable.
+/// Panics in debug builds.
+///
+/// # Safety
+///
+/// Must never be called.
 #[cfg(not(debug_assertions))]
-pub fn unreachable_unchecked() -> ! {
+pub unsafe fn unreachable_unchecked() -> ! {
     core::hint::unreachable_unchecked()
 }
@@ -1,29 +1,41 @@
 use {
     crate::{
-        archetype::{Archetype, ArchetypeError, ArchetypeStorage},
-        bundle::Bundle,
-        component::ComponentInfo,
-        entity::{Entity, EntityLocations, Location},
-        query::{Access, AccessKind, ArchetypeAccess, View},
-        util::{unreachable_unchecked, AsyncLock, TypeIdListMap, TypeIdMap},
+        archetype::{Archetype, ArchetypeError, ArchetypeStorage, TakeComponents},
+        bundle::{Bundle, StaticBundle},
+        component::{Component, ComponentHook, ComponentId, ComponentInfo, ComponentRegistry},
+        entity::{Entity, EntityLocations, Location, TooManyEntities},
+        events::{update_events, Events},
+        query::{
+            iter::{query_archetype_chunks, query_archetypes},
+            Access, ArchetypeAccess, View,
+        },
+        removed::RemovedLog,
+        resource::Resource,
+        util::{AsyncLock, BitSet, SignatureMap, TypeIdMap},
     },
     alloc::{boxed::Box, vec::Vec},
     core::{
         any::TypeId,
-        cmp::{Ord, Ordering},
-        future::Future,
-        pin::Pin,
-        sync::atomic::{AtomicUsize, Ordering::*},
-        task::{Context, Poll, Waker},
+        cell::Cell,
+        mem::replace,
+        sync::atomic::{AtomicU64, Ordering::Relaxed},
     },
-    hashbrown::hash_map::RawEntryMut,
-    spin::Mutex,
+};
+
+#[cfg(feature = "parallel")]
+use {
+    crate::query::par::{par_for_each_archetypes, par_query_archetype_chunks},
+    rayon::iter::IndexedParallelIterator,
 };
 
 pub(crate) struct ArchetypeData {
     storage: ArchetypeStorage,
-    // with: TypeIdListMap<usize>,
     locks: Box<[AsyncLock]>,
+
+    /// Accesses granted to queries on `&mut World`.
+    /// Exclusive borrow of the world grants access to all components.
+    /// Viewed as cells only under exclusive borrow, which keeps `World` `Sync`.
+    granted: Box<[usize]>,
 }
 
 impl ArchetypeData {
@@ -34,22 +46,37 @@ impl ArchetypeData {
     pub(crate) fn locks(&self) -> &[AsyncLock] {
         &self.locks
     }
+
+    /// Checks that archetype has all components from `required` signature.
+    /// `None` stands for components never registered, so no archetype has them.
+    pub(crate) fn has_all(&self, required: Option<&BitSet>) -> bool {
+        required.is_some_and(|required| self.storage.signature().is_superset(required))
+    }
 }
 
 impl ArchetypeData {
-    fn new(components: Box<[ComponentInfo]>) -> Result<Self, ArchetypeError> {
-        let archetype = Archetype::new(components)?;
+    fn new(
+        components: Vec<ComponentInfo>,
+        column_align: usize,
+        registry: &mut ComponentRegistry,
+    ) -> Result<Self, ArchetypeError> {
+        let archetype = Archetype::with_column_align(components, column_align)?;
+        let ids = archetype
+            .infos()
+            .map(|info| registry.register(&info))
+            .collect();
         let locks = archetype
             .components()
             .iter()
             .map(|_| AsyncLock::new())
             .collect();
-        let storage = ArchetypeStorage::new(archetype);
+        let granted = archetype.components().iter().map(|_| usize::MAX).collect();
+        let storage = ArchetypeStorage::new(archetype, ids);
 
         Ok(ArchetypeData {
             storage,
-            // with: TypeIdListMap::default(),
             locks,
+            granted,
         })
     }
 }
@@ -59,80 +86,320 @@ impl ArchetypeData {
 #[derive(Clone, Copy, Debug)]
 pub struct NoSuchEntity;
 
+/// Error occuring when components are removed from an entity.
+#[derive(Clone, Copy, Debug)]
+pub enum RemoveError {
+    /// Referenced entity does not exist.
+    NoSuchEntity,
+
+    /// Entity does not have some of the components.
+    MissingComponents,
+}
+
+impl From<NoSuchEntity> for RemoveError {
+    fn from(_: NoSuchEntity) -> Self {
+        RemoveError::NoSuchEntity
+    }
+}
+
+/// Error occuring when view of specific entities is requested.
+#[derive(Clone, Copy, Debug)]
+pub enum GetError {
+    /// Referenced entity does not exist.
+    NoSuchEntity,
+
+    /// Entity does not match the view.
+    MissingComponents,
+
+    /// Access required by the view is not locked.
+    NotLocked,
+
+    /// Same entity is referenced more than once.
+    DuplicateEntity,
+}
+
+impl From<NoSuchEntity> for GetError {
+    fn from(_: NoSuchEntity) -> Self {
+        GetError::NoSuchEntity
+    }
+}
+
+static NEXT_WORLD_ID: AtomicU64 = AtomicU64::new(0);
+
 /// World is container for entities.
 pub struct World {
     archetypes: Vec<ArchetypeData>,
-    archetype_map: TypeIdListMap<usize>,
+    archetype_map: SignatureMap<usize>,
+    registry: ComponentRegistry,
     entities: EntityLocations,
+    column_align: usize,
+
+    /// Scratch space for signature lookups.
+    signature: BitSet,
+
+    /// Current tick. Advanced by `World::maintain`.
+    tick: u64,
+
+    /// Logs of removed components indexed by `ComponentId`.
+    removed: Vec<RemovedLog>,
+
+    /// Number of maintenance cycles removal logs are kept for.
+    removed_retention: u64,
+
+    resources: TypeIdMap<Resource>,
+
+    /// Functions rotating event channels stored as resources.
+    event_channels: TypeIdMap<fn(&mut Resource)>,
+
+    /// Unique id of the world.
+    id: u64,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl World {
     /// Create new empty `World`.
     pub fn new() -> Self {
+        Self::with_column_align(1)
+    }
+
+    /// Create new empty `World`.
+    /// Each component column in archetype chunks is aligned to at least `column_align`.
+    /// Using cache line size prevents false sharing between columns
+    /// accessed from different threads.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `column_align` is not power of two.
+    pub fn with_column_align(column_align: usize) -> Self {
+        assert!(
+            column_align.is_power_of_two(),
+            "Column alignment must be power of two"
+        );
+
         World {
             archetypes: Vec::new(),
-            archetype_map: TypeIdListMap::default(),
+            archetype_map: SignatureMap::default(),
+            registry: ComponentRegistry::default(),
             entities: EntityLocations::new(),
+            column_align,
+            signature: BitSet::new(),
+            tick: 1,
+            removed: Vec::new(),
+            removed_retention: 1,
+            resources: TypeIdMap::default(),
+            event_channels: TypeIdMap::default(),
+            id: NEXT_WORLD_ID.fetch_add(1, Relaxed),
         }
     }
 
+    /// Registers component type with hooks from `info`.
+    /// Replaces hooks registered for the type earlier.
+    pub fn register_component(&mut self, info: ComponentInfo) -> ComponentId {
+        self.registry.register_hooks(&info)
+    }
+
     /// Spawn new entity with components from `Bundle`.
     pub fn spawn(&mut self, bundle: impl Bundle + 'static) -> Entity {
-        let archetype =
-            bundle.with_ids(
-                |ids| match self.archetype_map.raw_entry_mut().from_key(ids) {
-                    RawEntryMut::Occupied(entry) => *entry.get(),
-                    RawEntryMut::Vacant(entry) => {
-                        let archetypes = &mut self.archetypes;
-                        bundle.with_components(move |components| {
-                            let archetype =
-                                ArchetypeData::new(components.into()).expect("Too large bundle");
-                            archetypes.push(archetype);
-
-                            let (_, v) = entry.insert(ids.into(), archetypes.len() - 1);
-                            *v
-                        })
-                    }
-                },
-            );
+        let archetype = self.archetype_for_bundle(&bundle);
+        let hooks = bundle.with_ids(|ids| self.insert_hooks(usize::MAX, ids));
 
         let entity = self.entities.spawn_mut();
 
         let index = self.archetypes[archetype]
             .storage
-            .insert(bundle, entity.index());
+            .insert(bundle, entity.index(), self.tick);
 
         self.entities
             .relocate(entity, Location { archetype, index });
 
+        self.run_hooks(entity, hooks);
         entity
     }
 
+    /// Inserts components from `Bundle` into existing entity.
+    /// Entity is moved to the archetype with additional components.
+    ///
+    /// Components that entity already has are replaced in place.
+    /// Returns replaced components, `None` for components entity did not have.
+    pub fn insert<B>(&mut self, entity: Entity, bundle: B) -> Result<B::Optional, NoSuchEntity>
+    where
+        B: StaticBundle + 'static,
+    {
+        let location = self.entities.locate(entity).ok_or(NoSuchEntity)?;
+
+        if location.archetype == usize::MAX {
+            let archetype = self.archetype_for_bundle(&bundle);
+            let hooks = B::static_with_ids(|ids| self.insert_hooks(usize::MAX, ids));
+
+            let index =
+                self.archetypes[archetype]
+                    .storage
+                    .insert(bundle, entity.index(), self.tick);
+
+            self.entities
+                .relocate(entity, Location { archetype, index });
+
+            self.run_hooks(entity, hooks);
+            return Ok(B::take_optional(TakeComponents::empty()));
+        }
+
+        let src = location.archetype;
+        let dst = self.archetype_with_bundle(src, &bundle);
+        let hooks = B::static_with_ids(|ids| self.insert_hooks(src, ids));
+
+        if src == dst {
+            // Entity has all components from the bundle.
+            let replaced = unsafe {
+                // SAFETY: Entity is alive, so `location.index` is less than storage length.
+                // Archetype contains all components from the bundle.
+                self.archetypes[src]
+                    .storage
+                    .replace(bundle, location.index, self.tick)
+            };
+
+            self.run_hooks(entity, hooks);
+            return Ok(replaced);
+        }
+
+        let index = unsafe {
+            // SAFETY: Remaining components are moved from source archetype below.
+            self.archetypes[dst]
+                .storage
+                .insert_partial(bundle, entity.index(), self.tick)
+        };
+
+        B::static_with_ids(|ids| unsafe {
+            // SAFETY: Destination archetype contains all components of source archetype.
+            // Components from bundle are not moved.
+            self.archetypes[src].storage.move_components_except(
+                ids,
+                location.index,
+                &self.archetypes[dst].storage,
+                index,
+            )
+        });
+
+        self.entities.relocate(
+            entity,
+            Location {
+                archetype: dst,
+                index,
+            },
+        );
+
+        let removed = unsafe {
+            // SAFETY: Components of removed entity are either moved or taken below.
+            self.swap_remove(location)
+        };
+
+        let replaced = unsafe {
+            // SAFETY: Removed entity components are left at `removed` index.
+            // Components from bundle were not moved.
+            self.archetypes[src]
+                .storage
+                .take_optional_components::<B>(removed)
+        };
+
+        self.run_hooks(entity, hooks);
+        Ok(replaced)
+    }
+
+    /// Removes components of the `Bundle` from existing entity.
+    /// Entity is moved to the archetype without removed components.
+    /// Returns removed components.
+    ///
+    /// If entity misses some of the components nothing is removed.
+    pub fn remove<B>(&mut self, entity: Entity) -> Result<B, RemoveError>
+    where
+        B: StaticBundle + 'static,
+    {
+        B::static_with_ids(|ids| self.run_remove_hooks(entity, ids))?;
+        let removed = B::static_with_ids(|ids| self.remove_components(entity, ids))?;
+
+        let storage = &mut self.archetypes[removed.archetype].storage;
+
+        Ok(unsafe {
+            // SAFETY: Removed entity components are left at `removed` index.
+            // Components from bundle were not moved.
+            storage.take_components(removed.index)
+        })
+    }
+
+    /// Removes components with specified ids from existing entity.
+    /// Entity is moved to the archetype without removed components.
+    /// Removed components are dropped.
+    ///
+    /// If entity misses some of the components nothing is removed.
+    pub fn remove_dyn(&mut self, entity: Entity, ids: &[TypeId]) -> Result<(), RemoveError> {
+        self.run_remove_hooks(entity, ids)?;
+        let removed = self.remove_components(entity, ids)?;
+
+        let storage = &mut self.archetypes[removed.archetype].storage;
+
+        unsafe {
+            // SAFETY: Removed entity components are left at `removed` index.
+            // Components with specified ids were not moved.
+            storage.drop_components(ids, removed.index)
+        }
+        Ok(())
+    }
+
     /// Returns component of specified entity.
-    pub fn get_ref<T: 'static>(&self, entity: Entity) -> Result<Option<&T>, NoSuchEntity> {
+    ///
+    /// Requires exclusive borrow, as components may be locked mutably
+    /// through shared borrows of the world.
+    pub fn get_ref<T: 'static>(&mut self, entity: Entity) -> Result<Option<&T>, NoSuchEntity> {
         let location = self.entities.locate(entity).ok_or(NoSuchEntity)?;
 
         if location.archetype == usize::MAX {
             Ok(None)
         } else {
+            let id = match self.registry.get(TypeId::of::<T>()) {
+                Some(id) => id,
+                None => return Ok(None),
+            };
             let storage = &self.archetypes[location.archetype].storage;
-            Ok(storage.get_component_ref(entity.index()))
+            Ok(storage.get_component_ref(id, location.index))
         }
     }
 
     /// Returns component of specified entity.
+    /// Component is marked as changed at current tick.
     pub fn get_mut<T: 'static>(&mut self, entity: Entity) -> Result<Option<&mut T>, NoSuchEntity> {
         let location = self.entities.locate(entity).ok_or(NoSuchEntity)?;
 
         if location.archetype == usize::MAX {
             Ok(None)
         } else {
+            let id = match self.registry.get(TypeId::of::<T>()) {
+                Some(id) => id,
+                None => return Ok(None),
+            };
             let storage = &mut self.archetypes[location.archetype].storage;
-            Ok(storage.get_component_mut(entity.index()))
+            Ok(storage.get_component_mut(id, location.index, self.tick))
+        }
+    }
+
+    /// Reserves new entity without components.
+    /// Unlike `World::spawn` this method can be called concurrently.
+    ///
+    /// Reserved entity is materialized on `World::maintain`
+    /// or any other operation that requires mutable access.
+    pub fn reserve(&self) -> Entity {
+        match self.entities.spawn() {
+            Ok(entity) => entity,
+            Err(TooManyEntities) => panic!("Too many entities reserved"),
         }
     }
 
     /// Despawn an entity dropping all its commponents.
+    ///
+    /// Despawning is deferred until `World::maintain` is called.
     pub fn despawn(&self, entity: Entity) -> Result<(), NoSuchEntity> {
         if self.entities.despawn(entity) {
             Ok(())
@@ -141,7 +408,751 @@ impl World {
         }
     }
 
+    /// Performs deferred operations.
+    /// Drops components of despawned entities
+    /// and materializes reserved entities.
+    /// Advances current tick, drops expired removal log entries
+    /// and rotates event channels.
+    ///
+    /// `on_remove` hooks of despawned entities run before their components are dropped.
+    pub fn maintain(&mut self) {
+        if self.registry.has_hooks() {
+            self.run_despawn_hooks();
+        }
+
+        let World {
+            archetypes,
+            entities,
+            removed,
+            tick,
+            ..
+        } = self;
+
+        entities.flush(|entity, location| {
+            if location.archetype == usize::MAX {
+                None
+            } else {
+                let archetype = &mut archetypes[location.archetype];
+                log_removed(removed, archetype.storage.signature().iter(), *tick, entity);
+                archetype.storage.remove(location.index)
+            }
+        });
+
+        self.tick += 1;
+
+        let before = self.tick.saturating_sub(self.removed_retention);
+        for log in &mut self.removed {
+            log.prune(before);
+        }
+
+        for (id, update) in &self.event_channels {
+            if let Some(resource) = self.resources.get_mut(id) {
+                update(resource);
+            }
+        }
+    }
+
+    /// Sets number of `World::maintain` calls entries of removal logs are kept for.
+    /// Entries logged since the previous call are always kept.
+    /// Default is 1, so `RemovedComponents` readers have to run once per cycle.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `maintains` is zero.
+    pub fn set_removed_retention(&mut self, maintains: u64) {
+        assert!(
+            maintains > 0,
+            "Removal logs must be kept for at least one cycle"
+        );
+        self.removed_retention = maintains;
+    }
+
+    /// Returns iterator over views of all entities that match the view.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// Iteration panics if view borrows same components in conflicting ways.
+    pub fn query<'a, V>(&'a mut self) -> impl Iterator<Item = <V as View<'a>>::EntityView> + 'a
+    where
+        V: View<'a> + Access + Default + 'a,
+    {
+        self.query_with(V::default())
+    }
+
+    /// Returns iterator over views of all entities that match specified view.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// Iteration panics if view borrows same components in conflicting ways.
+    pub fn query_with<'a, V>(
+        &'a mut self,
+        view: V,
+    ) -> impl Iterator<Item = <V as View<'a>>::EntityView> + 'a
+    where
+        V: View<'a> + Access + 'a,
+    {
+        let required = self.required_signature(&view);
+        query_archetypes(self.archetype_accesses(required), view)
+    }
+
+    /// Returns iterator over views of all chunks that match the view.
+    /// Each chunk view contains slices of components of all entities in the chunk.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// Iteration panics if view borrows same components in conflicting ways.
+    pub fn query_chunks<'a, V>(
+        &'a mut self,
+    ) -> impl Iterator<Item = <V as View<'a>>::ChunkView> + 'a
+    where
+        V: View<'a> + Access + Default + 'a,
+    {
+        self.query_chunks_with(V::default())
+    }
+
+    /// Returns iterator over views of all chunks that match specified view.
+    /// Each chunk view contains slices of components of all entities in the chunk.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// Iteration panics if view borrows same components in conflicting ways.
+    pub fn query_chunks_with<'a, V>(
+        &'a mut self,
+        view: V,
+    ) -> impl Iterator<Item = <V as View<'a>>::ChunkView> + 'a
+    where
+        V: View<'a> + Access + 'a,
+    {
+        let required = self.required_signature(&view);
+        query_archetype_chunks(self.archetype_accesses(required), view)
+    }
+
+    /// Returns parallel iterator over views of all chunks that match the view.
+    /// Chunks are yielded in the same order as by `query_chunks`.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    #[cfg(feature = "parallel")]
+    pub fn par_iter_chunks<'a, V>(
+        &'a mut self,
+    ) -> impl IndexedParallelIterator<Item = <V as View<'a>>::ChunkView> + 'a
+    where
+        V: View<'a> + Access + Default + 'a,
+        V::ChunkView: Send,
+    {
+        self.par_iter_chunks_with(V::default())
+    }
+
+    /// Returns parallel iterator over views of all chunks that match specified view.
+    /// Chunks are yielded in the same order as by `query_chunks_with`.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    #[cfg(feature = "parallel")]
+    pub fn par_iter_chunks_with<'a, V>(
+        &'a mut self,
+        view: V,
+    ) -> impl IndexedParallelIterator<Item = <V as View<'a>>::ChunkView> + 'a
+    where
+        V: View<'a> + Access + 'a,
+        V::ChunkView: Send,
+    {
+        let required = self.required_signature(&view);
+        par_query_archetype_chunks(self.archetype_accesses(required), view)
+    }
+
+    /// Calls `f` with views of all entities that match the view.
+    /// Chunks are processed in parallel on rayon's thread pool.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    #[cfg(feature = "parallel")]
+    pub fn par_for_each<'a, V, F>(&'a mut self, f: F)
+    where
+        V: View<'a> + Access + Default + 'a,
+        V::ChunkRefs: Send,
+        F: Fn(<V as View<'a>>::EntityView) + Sync,
+    {
+        self.par_for_each_with(V::default(), f)
+    }
+
+    /// Calls `f` with views of all entities that match specified view.
+    /// Chunks are processed in parallel on rayon's thread pool.
+    ///
+    /// Exclusive borrow of the world guarantees that there are no other accesses,
+    /// so no locks are acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if view borrows same components in conflicting ways.
+    #[cfg(feature = "parallel")]
+    pub fn par_for_each_with<'a, V, F>(&'a mut self, view: V, f: F)
+    where
+        V: View<'a> + Access + 'a,
+        V::ChunkRefs: Send,
+        F: Fn(<V as View<'a>>::EntityView) + Sync,
+    {
+        let required = self.required_signature(&view);
+        par_for_each_archetypes(self.archetype_accesses(required), view, f)
+    }
+
+    /// Grants all accesses to archetypes with all `required` components.
+    fn archetype_accesses(
+        &mut self,
+        required: Option<BitSet>,
+    ) -> impl Iterator<Item = ArchetypeAccess<'_>> + '_ {
+        let registry = &self.registry;
+        let entities = &self.entities;
+        let tick = self.tick;
+        let archetypes = self.archetypes.iter_mut();
+        archetypes
+            .filter(move |archetype| archetype.has_all(required.as_ref()))
+            .map(move |archetype| {
+                let ArchetypeData {
+                    storage, granted, ..
+                } = archetype;
+
+                // Reset accesses leaked by forgotten iterators.
+                for granted in granted.iter_mut() {
+                    *granted = usize::MAX;
+                }
+
+                ArchetypeAccess::new(
+                    Cell::from_mut(&mut **granted).as_slice_of_cells(),
+                    storage,
+                    registry,
+                    entities,
+                    tick,
+                )
+            })
+    }
+
+    /// Despawns all entities dropping their components.
+    /// Archetypes and allocated memory are kept for reuse.
+    pub fn clear(&mut self) {
+        if self.registry.has_hooks() {
+            let entities: Vec<Entity> = self
+                .archetypes
+                .iter()
+                .flat_map(|archetype| archetype.storage.entity_indices())
+                .map(|index| self.entities.get_entity(index))
+                .collect();
+
+            for entity in entities {
+                let hooks = self.despawn_hooks(entity);
+                self.run_hooks(entity, hooks);
+            }
+        }
+
+        for archetype in &mut self.archetypes {
+            for index in archetype.storage.entity_indices() {
+                let entity = self.entities.get_entity(index);
+                log_removed(
+                    &mut self.removed,
+                    archetype.storage.signature().iter(),
+                    self.tick,
+                    entity,
+                );
+            }
+            archetype.storage.clear();
+        }
+
+        self.entities.clear();
+    }
+
+    /// Inserts resource of type `T`.
+    /// Returns previous value if any.
+    pub fn insert_resource<T: Component>(&mut self, value: T) -> Option<T> {
+        match self.resources.get_mut(&TypeId::of::<T>()) {
+            Some(resource) => resource.get_mut().map(|old| replace(old, value)),
+            None => {
+                self.resources
+                    .insert(TypeId::of::<T>(), Resource::new(value));
+                None
+            }
+        }
+    }
+
+    /// Removes resource of type `T`.
+    /// Removed events channel is no longer rotated by `World::maintain`.
+    pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
+        self.event_channels.remove(&TypeId::of::<T>());
+        self.resources
+            .remove(&TypeId::of::<T>())
+            .and_then(Resource::into_inner)
+    }
+
+    /// Returns resource of type `T`.
+    ///
+    /// Requires exclusive borrow, as resources may be locked mutably
+    /// through shared borrows of the world.
+    pub fn resource<T: 'static>(&mut self) -> Option<&T> {
+        let resource = self.resources.get_mut(&TypeId::of::<T>())?;
+        resource.get_mut().map(|value| &*value)
+    }
+
+    /// Returns resource of type `T`.
+    pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources.get_mut(&TypeId::of::<T>())?.get_mut()
+    }
+
+    /// Adds channel for events of type `T` unless already added.
+    /// Channel is stored as `Events<T>` resource and rotated by `World::maintain`.
+    pub fn add_events<T: Component>(&mut self) -> &mut Events<T> {
+        let id = TypeId::of::<Events<T>>();
+        self.event_channels.insert(id, update_events::<T>);
+
+        self.resources
+            .entry(id)
+            .or_insert_with(|| Resource::new(Events::<T>::new()))
+            .get_mut()
+            .expect("Resource of wrong type")
+    }
+
+    /// Sends event of type `T`.
+    /// Adds channel for events of type `T` if needed.
+    pub fn send_event<T: Component>(&mut self, event: T) {
+        self.add_events::<T>().send(event);
+    }
+
+    /// Returns current tick.
+    /// Components are stamped with current tick when they are added or borrowed mutably,
+    /// and `Changed` and `Added` filters compare against these stamps.
+    /// Tick is advanced by each call to `World::maintain`.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns archetype generation.
+    /// Generation increases each time new archetype is created,
+    /// so cached query plans need to be updated only when it changes.
+    pub fn archetype_generation(&self) -> usize {
+        // Archetypes are never removed.
+        self.archetypes.len()
+    }
+
+    /// Returns dense id of the component type.
+    /// Ids are assigned when component type is registered or first appears in an archetype of this world,
+    /// so `None` means no entity has ever had components of this type.
+    pub fn component_id(&self, id: TypeId) -> Option<ComponentId> {
+        self.registry.get(id)
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub(crate) fn registry(&self) -> &ComponentRegistry {
+        &self.registry
+    }
+
     pub(crate) fn archetypes(&self) -> &[ArchetypeData] {
         &self.archetypes
     }
+
+    pub(crate) fn entities(&self) -> &EntityLocations {
+        &self.entities
+    }
+
+    pub(crate) fn resources(&self) -> &TypeIdMap<Resource> {
+        &self.resources
+    }
+
+    pub(crate) fn removed_log(&self, id: ComponentId) -> Option<&RemovedLog> {
+        self.removed.get(id.index())
+    }
+
+    /// Returns index of the archetype with components from the bundle.
+    /// Creates new archetype if not found.
+    fn archetype_for_bundle(&mut self, bundle: &impl Bundle) -> usize {
+        let World {
+            archetypes,
+            archetype_map,
+            registry,
+            column_align,
+            signature,
+            ..
+        } = self;
+
+        bundle.with_ids(|ids| {
+            if signature_of(registry, ids, signature) {
+                if let Some(&index) = archetype_map.get(&*signature) {
+                    return index;
+                }
+            }
+
+            bundle.with_components(|components| {
+                let archetype = ArchetypeData::new(components.into(), *column_align, registry)
+                    .expect("Too large bundle");
+                push_archetype(archetypes, archetype_map, archetype)
+            })
+        })
+    }
+
+    /// Returns `on_add` hooks of components missing in `src` archetype
+    /// followed by `on_insert` hooks of all components with specified ids.
+    fn insert_hooks(&self, src: usize, ids: &[TypeId]) -> Vec<ComponentHook> {
+        if !self.registry.has_hooks() {
+            return Vec::new();
+        }
+
+        let src = self
+            .archetypes
+            .get(src)
+            .map(|archetype| archetype.storage.signature());
+        let ids = ids.iter().filter_map(|&id| self.registry.get(id));
+
+        let mut hooks: Vec<_> = ids
+            .clone()
+            .filter(|id| !src.is_some_and(|src| src.contains(id.index())))
+            .filter_map(|id| self.registry.info(id.index()).add_hook())
+            .collect();
+
+        hooks.extend(ids.filter_map(|id| self.registry.info(id.index()).insert_hook()));
+        hooks
+    }
+
+    /// Runs `on_remove` hooks of components with specified ids.
+    /// Fails without running hooks if entity misses some of the components.
+    fn run_remove_hooks(&mut self, entity: Entity, ids: &[TypeId]) -> Result<(), RemoveError> {
+        if !self.registry.has_hooks() {
+            return Ok(());
+        }
+
+        let location = self.entities.locate(entity).ok_or(NoSuchEntity)?;
+        let archetype = self
+            .archetypes
+            .get(location.archetype)
+            .ok_or(RemoveError::MissingComponents)?;
+
+        let mut hooks = Vec::new();
+        for &id in ids {
+            match self.registry.get(id) {
+                Some(id) if archetype.storage.signature().contains(id.index()) => {
+                    hooks.extend(self.registry.info(id.index()).remove_hook())
+                }
+                _ => return Err(RemoveError::MissingComponents),
+            }
+        }
+
+        self.run_hooks(entity, hooks);
+        Ok(())
+    }
+
+    /// Returns `on_remove` hooks of all components of the entity.
+    fn despawn_hooks(&self, entity: Entity) -> Vec<ComponentHook> {
+        let archetype = match self.entities.locate(entity) {
+            Some(location) => self.archetypes.get(location.archetype),
+            None => None,
+        };
+
+        match archetype {
+            Some(archetype) => archetype
+                .storage
+                .signature()
+                .iter()
+                .filter_map(|id| self.registry.info(id).remove_hook())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Runs `on_remove` hooks of entities scheduled for despawn.
+    /// Entities despawned by hooks are handled as well.
+    fn run_despawn_hooks(&mut self) {
+        let mut despawned = Vec::new();
+        let mut visited = BitSet::new();
+        let mut start = 0;
+
+        loop {
+            self.entities.drain_despawned(&mut despawned);
+            if start == despawned.len() {
+                break;
+            }
+
+            for &entity in &despawned[start..] {
+                if visited.contains(entity.index()) || self.entities.locate(entity).is_none() {
+                    continue;
+                }
+                visited.insert(entity.index());
+
+                let hooks = self.despawn_hooks(entity);
+                self.run_hooks(entity, hooks);
+            }
+            start = despawned.len();
+        }
+
+        for entity in despawned {
+            self.entities.despawn_mut(entity);
+        }
+    }
+
+    fn run_hooks(&mut self, entity: Entity, hooks: Vec<ComponentHook>) {
+        for hook in hooks {
+            hook(self, entity);
+        }
+    }
+
+    /// Moves entity to the archetype without specified components.
+    /// Returns location where removed components are left.
+    /// Caller is responsible to take or drop them.
+    fn remove_components(
+        &mut self,
+        entity: Entity,
+        ids: &[TypeId],
+    ) -> Result<Location, RemoveError> {
+        let location = self.entities.locate(entity).ok_or(NoSuchEntity)?;
+
+        if location.archetype == usize::MAX {
+            return Err(RemoveError::MissingComponents);
+        }
+
+        let src = location.archetype;
+        let dst = self
+            .archetype_without(src, ids)
+            .ok_or(RemoveError::MissingComponents)?;
+
+        let index = unsafe {
+            // SAFETY: Remaining components are moved from source archetype below.
+            self.archetypes[dst]
+                .storage
+                .insert_partial((), entity.index(), self.tick)
+        };
+
+        unsafe {
+            // SAFETY: Source archetype contains all components of destination archetype.
+            // Removed components are not moved.
+            self.archetypes[src].storage.move_components_except(
+                ids,
+                location.index,
+                &self.archetypes[dst].storage,
+                index,
+            )
+        };
+
+        self.entities.relocate(
+            entity,
+            Location {
+                archetype: dst,
+                index,
+            },
+        );
+
+        let removed = unsafe {
+            // SAFETY: Removed components are taken or dropped by caller.
+            self.swap_remove(location)
+        };
+
+        let registry = &self.registry;
+        log_removed(
+            &mut self.removed,
+            ids.iter()
+                .filter_map(|&id| registry.get(id))
+                .map(|id| id.index()),
+            self.tick,
+            entity,
+        );
+
+        Ok(Location {
+            archetype: src,
+            index: removed,
+        })
+    }
+
+    /// Removes entity at specified location from archetype storage
+    /// without dropping its components.
+    /// Returns index where components of the removed entity are left.
+    ///
+    /// # Safety
+    ///
+    /// Location must be valid.
+    /// Caller is responsible to move or drop components of the removed entity.
+    unsafe fn swap_remove(&mut self, location: Location) -> usize {
+        let storage = &mut self.archetypes[location.archetype].storage;
+
+        let moved = storage.swap_remove(location.index);
+        let removed = storage.len();
+
+        if let Some(moved) = moved {
+            self.entities.relocate_index(moved, location);
+        }
+
+        removed
+    }
+
+    /// Returns index of the archetype with components from specified archetype and the bundle.
+    /// Creates new archetype if not found.
+    fn archetype_with_bundle(&mut self, src: usize, bundle: &impl Bundle) -> usize {
+        let World {
+            archetypes,
+            archetype_map,
+            registry,
+            column_align,
+            signature,
+            ..
+        } = self;
+
+        bundle.with_ids(|ids| {
+            signature.copy_from(archetypes[src].storage.signature());
+
+            let mut registered = true;
+            for &id in ids {
+                match registry.get(id) {
+                    Some(component) => signature.insert(component.index()),
+                    None => registered = false,
+                }
+            }
+
+            if registered {
+                if *signature == *archetypes[src].storage.signature() {
+                    return src;
+                }
+
+                if let Some(&index) = archetype_map.get(&*signature) {
+                    return index;
+                }
+            }
+
+            bundle.with_components(|components| {
+                let src_archetype = archetypes[src].storage.archetype();
+
+                let components: Vec<ComponentInfo> = src_archetype
+                    .infos()
+                    .chain(
+                        components
+                            .iter()
+                            .filter(|c| !src_archetype.contains(c.id()))
+                            .copied(),
+                    )
+                    .collect();
+
+                let archetype = ArchetypeData::new(components, *column_align, registry)
+                    .expect("Too large bundle");
+                push_archetype(archetypes, archetype_map, archetype)
+            })
+        })
+    }
+
+    /// Returns index of the archetype with components from specified archetype except specified ones.
+    /// Creates new archetype if not found.
+    /// Returns `None` if specified archetype misses some of the components.
+    fn archetype_without(&mut self, src: usize, ids: &[TypeId]) -> Option<usize> {
+        let World {
+            archetypes,
+            archetype_map,
+            registry,
+            column_align,
+            signature,
+            ..
+        } = self;
+
+        let src_signature = archetypes[src].storage.signature();
+        signature.copy_from(src_signature);
+
+        for &id in ids {
+            match registry.get(id) {
+                Some(component) if src_signature.contains(component.index()) => {
+                    signature.remove(component.index())
+                }
+                _ => return None,
+            }
+        }
+
+        if let Some(&index) = archetype_map.get(&*signature) {
+            return Some(index);
+        }
+
+        let components: Vec<ComponentInfo> = archetypes[src]
+            .storage
+            .archetype()
+            .infos()
+            .filter(|c| !ids.contains(&c.id()))
+            .collect();
+
+        let archetype =
+            ArchetypeData::new(components, *column_align, registry).expect("Too large bundle");
+        Some(push_archetype(archetypes, archetype_map, archetype))
+    }
+
+    /// Returns signature of components with specified ids.
+    /// Returns `None` if some of the components were never registered,
+    /// so no archetype contains them.
+    pub(crate) fn signature(&self, ids: &[TypeId]) -> Option<BitSet> {
+        let mut signature = BitSet::new();
+        if signature_of(&self.registry, ids, &mut signature) {
+            Some(signature)
+        } else {
+            None
+        }
+    }
+
+    /// Returns signature of components required by the view.
+    pub(crate) fn required_signature(&self, access: &impl Access) -> Option<BitSet> {
+        access.with_required(|ids| self.signature(ids))
+    }
+}
+
+/// Writes signature of components with specified ids into `signature`.
+/// Returns `false` if some of the components were never registered.
+fn signature_of(registry: &ComponentRegistry, ids: &[TypeId], signature: &mut BitSet) -> bool {
+    signature.clear();
+    ids.iter().all(|&id| match registry.get(id) {
+        Some(component) => {
+            signature.insert(component.index());
+            true
+        }
+        None => false,
+    })
+}
+
+/// Logs removal of components with specified ids from the entity.
+/// Logs for components registered since last removal are created on demand.
+fn log_removed(
+    removed: &mut Vec<RemovedLog>,
+    ids: impl Iterator<Item = usize>,
+    tick: u64,
+    entity: Entity,
+) {
+    for id in ids {
+        if removed.len() <= id {
+            removed.resize_with(id + 1, RemovedLog::default);
+        }
+        removed[id].push(tick, entity);
+    }
+}
+
+/// Adds new archetype to the world and returns its index.
+fn push_archetype(
+    archetypes: &mut Vec<ArchetypeData>,
+    archetype_map: &mut SignatureMap<usize>,
+    archetype: ArchetypeData,
+) -> usize {
+    let index = archetypes.len();
+    archetype_map.insert(archetype.storage.signature().clone(), index);
+    archetypes.push(archetype);
+    index
 }
@@ -0,0 +1,289 @@
+mod common;
+
+use {
+    alex::{
+        added, changed, read, with, write, Added, AnyOf, Changed, Entities, GetError, Or,
+        PreparedQuery, Read, World, Write,
+    },
+    common::poll_once,
+    std::task::Poll,
+};
+
+#[derive(Debug, PartialEq)]
+struct Marker;
+
+fn changed_values(world: &mut World, since: u64) -> Vec<u32> {
+    let mut values: Vec<u32> = world
+        .query_with((read::<u32>(), changed::<u32>(since)))
+        .map(|(v, ())| *v)
+        .collect();
+    values.sort_unstable();
+    values
+}
+
+#[test]
+fn spawned_components_are_added_and_changed() {
+    let mut world = World::new();
+    let since = world.tick();
+
+    for i in 0..10u32 {
+        world.spawn((i, 0.0f32));
+    }
+    assert_eq!(changed_values(&mut world, since).len(), 10);
+    assert_eq!(world.query_with((added::<f32>(since),)).count(), 10);
+
+    world.maintain();
+    let since = world.tick();
+
+    assert!(changed_values(&mut world, since).is_empty());
+    assert_eq!(world.query_with((added::<f32>(since),)).count(), 0);
+}
+
+#[test]
+fn writes_mark_components_changed() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..100u32).map(|i| world.spawn((i, 0.0f32))).collect();
+    for &entity in entities.iter().step_by(10) {
+        world.insert(entity, (Marker,)).unwrap();
+    }
+
+    world.maintain();
+    let since = world.tick();
+
+    // Only yielded rows are marked.
+    for (value, ()) in world.query_with((write::<u32>(), with::<Marker>())) {
+        *value += 1000;
+    }
+    assert_eq!(
+        changed_values(&mut world, since),
+        (0..100).step_by(10).map(|i| i + 1000).collect::<Vec<_>>()
+    );
+
+    // Shared borrows don't mark anything.
+    for _ in world.query::<(Read<u32>, Read<f32>)>() {}
+    assert_eq!(
+        world
+            .query_with((read::<f32>(), changed::<f32>(since)))
+            .count(),
+        0
+    );
+
+    world.maintain();
+    let since = world.tick();
+
+    *world.get_mut::<u32>(entities[5]).unwrap().unwrap() = 5000;
+    assert_eq!(changed_values(&mut world, since), [5000]);
+
+    // Chunk views mark whole chunks.
+    world.maintain();
+    let since = world.tick();
+
+    for values in world.query_chunks::<(Write<u32>,)>() {
+        assert!(!values.0.is_empty());
+    }
+    assert_eq!(changed_values(&mut world, since).len(), 100);
+}
+
+#[test]
+fn unchanged_chunks_are_skipped() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..10000u32).map(|i| world.spawn((i,))).collect();
+    assert!(world.query_chunks::<(Read<u32>,)>().count() > 2);
+
+    world.maintain();
+    let since = world.tick();
+
+    *world.get_mut::<u32>(entities[7777]).unwrap().unwrap() = 0;
+
+    assert_eq!(changed_values(&mut world, since), [0]);
+    assert_eq!(
+        world
+            .query_chunks_with((read::<u32>(), changed::<u32>(since)))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn ticks_follow_moved_entities() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..10u32).map(|i| world.spawn((i,))).collect();
+
+    world.maintain();
+    let since = world.tick();
+
+    // Adding component keeps ticks of existing ones.
+    world.insert(entities[3], (1.0f32,)).unwrap();
+    assert!(changed_values(&mut world, since).is_empty());
+    assert_eq!(world.query_with((added::<f32>(since),)).count(), 1);
+
+    // Replacing component marks it changed but not added.
+    world.insert(entities[4], (44u32,)).unwrap();
+    assert_eq!(changed_values(&mut world, since), [44]);
+    assert_eq!(world.query_with((added::<u32>(since),)).count(), 0);
+
+    // Removing component keeps ticks of remaining ones.
+    world.remove::<(f32,)>(entities[3]).unwrap();
+    assert!(world.query_with((added::<u32>(since),)).next().is_none());
+
+    // Swap-removal moves ticks of the last entity.
+    world.despawn(entities[0]).unwrap();
+    world.maintain();
+    let since = world.tick();
+
+    *world.get_mut::<u32>(entities[9]).unwrap().unwrap() = 99;
+    world.despawn(entities[1]).unwrap();
+    world.maintain();
+
+    assert_eq!(changed_values(&mut world, since), [99]);
+}
+
+#[test]
+fn locked_filters() {
+    let mut world = World::new();
+    let old = world.spawn((1u32,));
+
+    world.maintain();
+    let since = world.tick();
+    let new = world.spawn((2u32,));
+
+    let mut lock = world.lock((read::<u32>(), changed::<u32>(since)));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    let values: Vec<u32> = access
+        .query_with((read::<u32>(), changed::<u32>(since)))
+        .map(|(v, ())| *v)
+        .collect();
+    assert_eq!(values, [2]);
+
+    assert!(matches!(
+        access.get_with((read::<u32>(), changed::<u32>(since)), old),
+        Err(GetError::MissingComponents)
+    ));
+    assert_eq!(
+        *access
+            .get_with((read::<u32>(), changed::<u32>(since)), new)
+            .unwrap()
+            .0,
+        2
+    );
+}
+
+#[test]
+fn prepared_filters() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..10u32).map(|i| world.spawn((i,))).collect();
+
+    let mut prepared = PreparedQuery::<(Entities, Changed<u32>)>::with_view((Entities, changed(0)));
+    assert_eq!(prepared.query(&mut world).count(), 10);
+
+    world.maintain();
+    prepared.view_mut().1.set_since(world.tick());
+    assert_eq!(prepared.query(&mut world).count(), 0);
+
+    *world.get_mut::<u32>(entities[2]).unwrap().unwrap() = 0;
+    let changed: Vec<_> = prepared.query(&mut world).map(|(e, ())| e).collect();
+    assert_eq!(changed, [entities[2]]);
+
+    let mut prepared =
+        PreparedQuery::<(Read<u32>, Added<u32>)>::with_view((read(), added(world.tick())));
+    assert_eq!(prepared.query(&mut world).count(), 0);
+
+    prepared.view_mut().1.set_since(0);
+    assert_eq!(prepared.query(&mut world).count(), 10);
+}
+
+#[test]
+fn write_with_changed_filter() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..10u32).map(|i| world.spawn((i,))).collect();
+
+    world.maintain();
+    let since = world.tick();
+    *world.get_mut::<u32>(entities[3]).unwrap().unwrap() = 30;
+
+    // Filter reads ticks of the column borrowed mutably.
+    for (value, ()) in world.query_with((write::<u32>(), changed::<u32>(since))) {
+        *value += 1;
+    }
+    assert_eq!(changed_values(&mut world, since), [31]);
+
+    let mut prepared =
+        PreparedQuery::<(Write<u32>, Changed<u32>)>::with_view((write(), changed(since)));
+    assert_eq!(prepared.query(&mut world).count(), 1);
+
+    world.maintain();
+    let since = world.tick();
+    *world.get_mut::<u32>(entities[5]).unwrap().unwrap() = 50;
+
+    // Filter alone locks components shared.
+    let mut filter = world.lock((changed::<u32>(since),));
+    let filter = match poll_once(&mut filter) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+    assert!(poll_once(&mut world.lock((write::<u32>(),))).is_pending());
+    drop(filter);
+
+    let mut lock = world.lock((write::<u32>(), changed::<u32>(since)));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+    let values: Vec<u32> = access
+        .query_with((write::<u32>(), changed::<u32>(since)))
+        .map(|(v, ())| *v)
+        .collect();
+    assert_eq!(values, [50]);
+    assert!(access
+        .get_with((write::<u32>(), changed::<u32>(since)), entities[5])
+        .is_ok());
+}
+
+#[test]
+fn changed_filters_in_any_of_and_or() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..10u32).map(|i| world.spawn((i, i as f32))).collect();
+
+    world.maintain();
+    let since = world.tick();
+
+    let any = || AnyOf((changed::<u32>(since), changed::<f32>(since)));
+    let or = || Or((changed::<u64>(since), changed::<f32>(since)));
+    assert_eq!(world.query_with((any(),)).count(), 0);
+    assert_eq!(world.query_with((or(),)).count(), 0);
+
+    *world.get_mut::<u32>(entities[3]).unwrap().unwrap() = 30;
+    *world.get_mut::<f32>(entities[7]).unwrap().unwrap() = 70.0;
+
+    // Only branches that pass are `Some`.
+    let mut rows: Vec<_> = world
+        .query_with((read::<u32>(), any()))
+        .map(|(v, flags)| (*v, flags))
+        .collect();
+    rows.sort_unstable_by_key(|&(v, _)| v);
+    assert_eq!(rows, [(7, (None, Some(()))), (30, (Some(()), None))]);
+
+    let rows: Vec<_> = world
+        .query_with((read::<u32>(), or()))
+        .map(|(v, flags)| (*v, flags))
+        .collect();
+    assert_eq!(rows, [(7, (false, true))]);
+
+    // Branches reading ticks borrow components they access.
+    let rows: Vec<_> = world
+        .query_with((Or(((read::<f32>(), changed::<f32>(since)), with::<u64>())),))
+        .map(|(flags,)| flags)
+        .collect();
+    assert_eq!(rows, [(true, false)]);
+
+    let mut lock = world.lock((read::<u32>(), or()));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+    assert_eq!(access.query_with((read::<u32>(), or())).count(), 1);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use {
+    alex::{read, write, Entities, Read, With, World, Write},
+    common::{poll_once, populate_bodies, Frozen, Pos, Vel},
+    std::task::Poll,
+};
+
+#[test]
+fn chunk_slices_cover_all_entities() {
+    let mut world = World::new();
+    populate_bodies(&mut world);
+
+    let mut chunks = 0;
+    let mut total = 0;
+    for (pos, vel) in world.query_chunks::<(Write<Pos>, Read<Vel>)>() {
+        assert_eq!(pos.len(), vel.len());
+        assert!(!pos.is_empty());
+        for (pos, vel) in pos.iter_mut().zip(vel) {
+            for (p, v) in pos.0.iter_mut().zip(&vel.0) {
+                *p += v;
+            }
+        }
+        chunks += 1;
+        total += pos.len();
+    }
+    assert_eq!(total, 10000);
+    assert!(chunks > 2, "Entities must span several chunks");
+
+    for (pos, _) in world.query::<(Read<Pos>, Read<Vel>)>() {
+        assert_eq!(pos.0[1] - pos.0[0], 1.0);
+        assert_eq!(pos.0[2] - pos.0[0], 2.0);
+    }
+
+    let total: usize = world.query_chunks::<Read<Pos>>().map(|pos| pos.len()).sum();
+    assert_eq!(total, 10100);
+}
+
+#[test]
+fn chunk_views_match_entity_views() {
+    let mut world = World::new();
+    populate_bodies(&mut world);
+
+    let per_entity: Vec<_> = world
+        .query::<(Entities, Read<Pos>, Option<Read<Vel>>)>()
+        .map(|(entity, pos, vel)| (entity, *pos, vel.copied()))
+        .collect();
+
+    let mut per_chunk = Vec::new();
+    for (entities, pos, vel) in world.query_chunks::<(Entities, Read<Pos>, Option<Read<Vel>>)>() {
+        assert_eq!(entities.len(), pos.len());
+        for (i, entity) in entities.iter().enumerate() {
+            assert_eq!(entities.get(i), Some(entity));
+            per_chunk.push((entity, pos[i], vel.map(|vel| vel[i])));
+        }
+        assert_eq!(entities.get(entities.len()), None);
+    }
+
+    assert_eq!(per_entity, per_chunk);
+
+    let frozen: usize = world
+        .query_chunks::<(Read<Pos>, With<Frozen>)>()
+        .map(|(pos, ())| pos.len())
+        .sum();
+    assert_eq!(frozen, 5000);
+}
+
+#[test]
+fn chunk_slices_through_lock() {
+    let mut world = World::new();
+    populate_bodies(&mut world);
+
+    let mut lock = world.lock((write::<Pos>(), read::<Vel>()));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    let mut total = 0;
+    for (pos, vel) in access.query_chunks::<(Write<Pos>, Read<Vel>)>() {
+        for (pos, vel) in pos.iter_mut().zip(vel) {
+            pos.0[0] = vel.0[2];
+        }
+        total += pos.len();
+    }
+    assert_eq!(total, 10000);
+
+    assert!(access
+        .query::<(Read<Pos>, Read<Vel>)>()
+        .all(|(pos, _)| pos.0[0] == 3.0));
+}
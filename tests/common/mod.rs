@@ -0,0 +1,62 @@
+//! Helpers shared by integration tests.
+
+#![allow(dead_code)]
+
+use {
+    alex::{Entity, World},
+    std::{
+        future::Future,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll, Wake, Waker},
+    },
+};
+
+/// Waker that does nothing.
+pub struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Polls future once with waker that does nothing.
+pub fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(Noop));
+    let mut ctx = Context::from_waker(&waker);
+    Pin::new(future).poll(&mut ctx)
+}
+
+/// Spawns `count` entities with `u32` components set to their spawn order.
+/// Every second of three entities also has `f32` and every third has `f32` and `u64`
+/// with the same value.
+pub fn populate(world: &mut World, count: u32) -> Vec<Entity> {
+    (0..count)
+        .map(|i| match i % 3 {
+            0 => world.spawn((i,)),
+            1 => world.spawn((i, i as f32)),
+            _ => world.spawn((i, i as f32, i as u64)),
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pos(pub [f32; 3]);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vel(pub [f32; 3]);
+
+pub struct Frozen;
+
+/// Spawns 10000 moving bodies, half of them `Frozen`, and 100 static ones.
+pub fn populate_bodies(world: &mut World) {
+    for i in 0..10000 {
+        let v = i as f32;
+        match i % 2 {
+            0 => world.spawn((Pos([v; 3]), Vel([1.0, 2.0, 3.0]))),
+            _ => world.spawn((Pos([v; 3]), Vel([1.0, 2.0, 3.0]), Frozen)),
+        };
+    }
+    for i in 0..100 {
+        world.spawn((Pos([i as f32; 3]),));
+    }
+}
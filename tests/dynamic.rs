@@ -0,0 +1,152 @@
+mod common;
+
+use {
+    alex::{AccessKind, DynamicQuery, Read, World},
+    common::{poll_once, populate},
+    std::{any::TypeId, task::Poll},
+};
+
+#[test]
+fn dynamic_rows() {
+    let mut world = World::new();
+    populate(&mut world, 1000);
+
+    let query = DynamicQuery::new()
+        .write(TypeId::of::<f32>())
+        .read(TypeId::of::<u32>())
+        .without(TypeId::of::<u64>());
+
+    let mut count = 0;
+    for row in world.query_with(query) {
+        let [float, int] = match row.components().collect::<Vec<_>>()[..] {
+            [float, int] => [float, int],
+            _ => panic!("Two components requested"),
+        };
+
+        // Order follows the query.
+        assert!(float.info().is::<f32>());
+        assert!(int.info().is::<u32>());
+        assert_eq!(float.kind(), AccessKind::Mutable);
+        assert_eq!(int.kind(), AccessKind::Shared);
+        assert_eq!(int.info().layout().size(), 4);
+        assert_eq!(row.get(TypeId::of::<u32>()).unwrap().ptr(), int.ptr());
+
+        unsafe {
+            let int = *int.ptr().cast::<u32>().as_ref();
+            *float.ptr().cast::<f32>().as_mut() = -(int as f32);
+        }
+        count += 1;
+    }
+    assert_eq!(count, 333);
+
+    for (int, float) in world.query::<(Read<u32>, Read<f32>)>() {
+        match int % 3 {
+            1 => assert_eq!(*float, -(*int as f32)),
+            _ => assert_eq!(*float, *int as f32),
+        }
+    }
+
+    let query = DynamicQuery::new().with(TypeId::of::<u64>());
+    let mut count = 0;
+    for row in world.query_with(query) {
+        assert!(row.components().next().is_none());
+        count += 1;
+    }
+    assert_eq!(count, 333);
+}
+
+#[test]
+fn dynamic_chunks() {
+    let mut world = World::new();
+    populate(&mut world, 1000);
+
+    let query = DynamicQuery::new().read(TypeId::of::<u32>());
+
+    let mut total = 0;
+    for chunk in world.query_chunks_with(query) {
+        let column = chunk.components().next().unwrap();
+        let values =
+            unsafe { std::slice::from_raw_parts(column.ptr().cast::<u32>().as_ptr(), chunk.len()) };
+        for (index, value) in values.iter().enumerate() {
+            let component = chunk.get(TypeId::of::<u32>(), index).unwrap();
+            assert_eq!(unsafe { *component.ptr().cast::<u32>().as_ref() }, *value);
+        }
+        assert!(chunk.get(TypeId::of::<u32>(), chunk.len()).is_none());
+        total += values.iter().map(|&v| v as u64).sum::<u64>();
+    }
+    assert_eq!(total, (0..1000).sum::<u64>());
+}
+
+#[test]
+fn dynamic_through_lock() {
+    let mut world = World::new();
+    populate(&mut world, 1000);
+
+    let query = DynamicQuery::new()
+        .write(TypeId::of::<u32>())
+        .read(TypeId::of::<f32>());
+
+    let mut lock = world.lock(query.clone());
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    // Other accesses to `u32` must wait.
+    let mut other = world.lock(DynamicQuery::new().read(TypeId::of::<u32>()));
+    assert!(poll_once(&mut other).is_pending());
+
+    let mut count = 0;
+    for row in access.query_with(query) {
+        unsafe {
+            *row.components()
+                .next()
+                .unwrap()
+                .ptr()
+                .cast::<u32>()
+                .as_mut() += 1
+        };
+        count += 1;
+    }
+    assert_eq!(count, 666);
+
+    drop(access);
+    drop(lock);
+
+    let mut access = match poll_once(&mut other) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is released"),
+    };
+    // Rows stay valid after iterator moves to next ones.
+    let rows: Vec<_> = access
+        .query_with(DynamicQuery::new().read(TypeId::of::<u32>()))
+        .collect();
+    let sum: u64 = rows
+        .iter()
+        .map(|row| unsafe {
+            *row.get(TypeId::of::<u32>())
+                .unwrap()
+                .ptr()
+                .cast::<u32>()
+                .as_ref()
+        } as u64)
+        .sum();
+    assert_eq!(sum, (0..1000).sum::<u64>() + 666);
+}
+
+#[test]
+#[should_panic(expected = "not granted")]
+fn dynamic_requires_lock() {
+    let mut world = World::new();
+    populate(&mut world, 1000);
+
+    let mut lock = world.lock(DynamicQuery::new().read(TypeId::of::<u32>()));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    access
+        .query_with(DynamicQuery::new().write(TypeId::of::<u32>()))
+        .for_each(drop);
+}
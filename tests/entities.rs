@@ -0,0 +1,75 @@
+mod common;
+
+use {
+    alex::{read, Entities, Entity, Read, World, Write},
+    common::{poll_once, populate},
+    std::{collections::HashMap, task::Poll},
+};
+
+#[test]
+fn entities_match_components() {
+    let mut world = World::new();
+    let spawned: HashMap<Entity, u32> = populate(&mut world, 1000).into_iter().zip(0..).collect();
+
+    let mut count = 0;
+    for (entity, value) in world.query::<(Entities, Read<u32>)>() {
+        assert_eq!(spawned[&entity], *value);
+        count += 1;
+    }
+    assert_eq!(count, spawned.len());
+
+    assert_eq!(world.query::<Entities>().count(), spawned.len());
+}
+
+#[test]
+fn despawn_queried_entities() {
+    let mut world = World::new();
+    let spawned: HashMap<Entity, u32> = populate(&mut world, 1000).into_iter().zip(0..).collect();
+
+    let odd: Vec<Entity> = world
+        .query::<(Entities, Read<u32>)>()
+        .filter(|(_, value)| *value % 2 == 1)
+        .map(|(entity, _)| entity)
+        .collect();
+    assert_eq!(odd.len(), 500);
+
+    for &entity in &odd {
+        world.despawn(entity).unwrap();
+    }
+    world.maintain();
+
+    // Entities moved by despawns are still reported correctly.
+    for (entity, value) in world.query::<(Entities, Write<u32>)>() {
+        assert_eq!(spawned[&entity], *value);
+        assert_eq!(*value % 2, 0);
+        *value += 1;
+    }
+
+    // Reused indices come with new generation.
+    let respawned: Vec<Entity> = (0..500u32).map(|i| world.spawn((i,))).collect();
+    for (entity, _) in world.query::<(Entities, Read<u32>)>() {
+        assert!(!odd.contains(&entity));
+    }
+    for &entity in &respawned {
+        assert!(world.get_ref::<u32>(entity).unwrap().is_some());
+    }
+}
+
+#[test]
+fn entities_through_lock() {
+    let mut world = World::new();
+    let spawned: HashMap<Entity, u32> = populate(&mut world, 1000).into_iter().zip(0..).collect();
+
+    let mut lock = world.lock((read::<u32>(), Entities));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    let mut count = 0;
+    for (value, entity) in access.query::<(Read<u32>, Entities)>() {
+        assert_eq!(spawned[&entity], *value);
+        count += 1;
+    }
+    assert_eq!(count, spawned.len());
+}
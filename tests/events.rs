@@ -0,0 +1,126 @@
+mod common;
+
+use {
+    alex::{read, read_events, write_events, EventCursor, Events, World},
+    common::poll_once,
+    std::task::Poll,
+};
+
+#[derive(Debug, PartialEq)]
+struct Hit(u32);
+
+fn read_hits(cursor: &mut EventCursor<Hit>, world: &mut World) -> Vec<u32> {
+    let events = world.resource::<Events<Hit>>().unwrap();
+    cursor.read(events).map(|hit| hit.0).collect()
+}
+
+#[test]
+fn readers_see_every_event_once() {
+    let mut world = World::new();
+    let mut first = EventCursor::new();
+    let mut second = EventCursor::new();
+
+    world.send_event(Hit(1));
+    world.send_event(Hit(2));
+    assert_eq!(read_hits(&mut first, &mut world), [1, 2]);
+    assert!(read_hits(&mut first, &mut world).is_empty());
+
+    world.send_event(Hit(3));
+    assert_eq!(read_hits(&mut first, &mut world), [3]);
+    assert_eq!(read_hits(&mut second, &mut world), [1, 2, 3]);
+
+    // Events sent before rotation are still readable.
+    world.maintain();
+    world.send_event(Hit(4));
+    assert_eq!(read_hits(&mut first, &mut world), [4]);
+    assert_eq!(read_hits(&mut second, &mut world), [4]);
+}
+
+#[test]
+fn events_rotate_on_maintain() {
+    let mut world = World::new();
+    world.add_events::<Hit>();
+    let mut lagging = EventCursor::new();
+
+    world.send_event(Hit(1));
+    world.maintain();
+    world.send_event(Hit(2));
+    assert_eq!(world.resource::<Events<Hit>>().unwrap().len(), 2);
+
+    world.maintain();
+    assert_eq!(world.resource::<Events<Hit>>().unwrap().len(), 1);
+    assert_eq!(read_hits(&mut lagging, &mut world), [2]);
+
+    world.maintain();
+    assert!(world.resource::<Events<Hit>>().unwrap().is_empty());
+    assert!(read_hits(&mut lagging, &mut world).is_empty());
+
+    // Channels inserted directly are not rotated.
+    world.insert_resource(Events::<u32>::new());
+    world.resource_mut::<Events<u32>>().unwrap().send(1);
+    world.maintain();
+    world.maintain();
+    assert_eq!(world.resource::<Events<u32>>().unwrap().len(), 1);
+}
+
+#[test]
+fn cursor_is_bound_to_channel() {
+    let mut first = World::new();
+    let mut second = World::new();
+    let mut cursor = EventCursor::new();
+
+    first.send_event(Hit(1));
+    first.send_event(Hit(2));
+    second.send_event(Hit(3));
+    assert_eq!(read_hits(&mut cursor, &mut first), [1, 2]);
+    assert_eq!(read_hits(&mut cursor, &mut second), [3]);
+    assert_eq!(read_hits(&mut cursor, &mut first), [1, 2]);
+
+    // Re-added channel starts anew and removed one is not rotated.
+    first.remove_resource::<Events<Hit>>().unwrap();
+    first.send_event(Hit(4));
+    assert_eq!(read_hits(&mut cursor, &mut first), [4]);
+
+    first.remove_resource::<Events<Hit>>().unwrap();
+    first.insert_resource(Events::<Hit>::new());
+    first.resource_mut::<Events<Hit>>().unwrap().send(Hit(5));
+    first.maintain();
+    first.maintain();
+    assert_eq!(read_hits(&mut cursor, &mut first), [5]);
+}
+
+#[test]
+fn locked_readers_and_writers() {
+    let mut world = World::new();
+    world.add_events::<Hit>();
+    world.spawn((1u32,));
+
+    let mut writer = world.lock((read::<u32>(), write_events::<Hit>()));
+    let mut access = match poll_once(&mut writer) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Locks are free"),
+    };
+
+    let mut reader = world.lock((read::<u32>(), read_events::<Hit>()));
+    assert!(poll_once(&mut reader).is_pending());
+
+    let values: Vec<u32> = access
+        .query_with((read::<u32>(),))
+        .map(|(value,)| *value)
+        .collect();
+    let events = access.resource_mut::<Events<Hit>>().unwrap();
+    for value in values {
+        events.send(Hit(value));
+    }
+    drop(access);
+
+    let access = match poll_once(&mut reader) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Locks are released"),
+    };
+    let mut cursor = EventCursor::new();
+    let hits: Vec<_> = cursor
+        .read(access.resource::<Events<Hit>>().unwrap())
+        .collect();
+    assert_eq!(hits, [&Hit(1)]);
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use {
+    alex::{read, with, without, write, AnyOf, Or, Read, With, Without, World, Write},
+    common::poll_once,
+    std::task::Poll,
+};
+
+struct Frozen;
+
+fn populate() -> World {
+    let mut world = World::new();
+    for i in 0..100u32 {
+        world.spawn((i, i as f32));
+    }
+    for i in 0..50u32 {
+        world.spawn((i, Frozen));
+    }
+    for i in 0..25u32 {
+        world.spawn((i,));
+    }
+    world
+}
+
+#[test]
+fn with_and_without() {
+    let mut world = populate();
+
+    assert_eq!(world.query::<(Read<u32>, With<Frozen>)>().count(), 50);
+    assert_eq!(world.query::<(Read<u32>, Without<Frozen>)>().count(), 125);
+    assert_eq!(
+        world
+            .query::<(Read<u32>, With<f32>, Without<Frozen>)>()
+            .count(),
+        100
+    );
+    assert_eq!(world.query::<(With<f32>, With<Frozen>)>().count(), 0);
+
+    for (value, ()) in world.query::<(Write<u32>, Without<f32>)>() {
+        *value += 1000;
+    }
+
+    assert!(world
+        .query::<(Read<u32>, With<f32>)>()
+        .all(|(value, ())| *value < 1000));
+    assert!(world
+        .query::<(Read<u32>, Without<f32>)>()
+        .all(|(value, ())| *value >= 1000));
+}
+
+#[test]
+fn optional_views() {
+    let mut world = populate();
+
+    let mut some = 0;
+    let mut none = 0;
+    for (value, float) in world.query::<(Read<u32>, Option<Read<f32>>)>() {
+        match float {
+            Some(float) => {
+                assert_eq!(*value as f32, *float);
+                some += 1;
+            }
+            None => none += 1,
+        }
+    }
+    assert_eq!(some, 100);
+    assert_eq!(none, 75);
+
+    for (value, float) in world.query::<(Read<u32>, Option<Write<f32>>)>() {
+        if let Some(float) = float {
+            *float += *value as f32;
+        }
+    }
+
+    assert!(world
+        .query::<(Read<u32>, Read<f32>)>()
+        .all(|(value, float)| *value as f32 * 2.0 == *float));
+
+    assert_eq!(world.query::<Option<Read<f32>>>().count(), 175);
+    assert_eq!(
+        world.query_with((Some(read::<u64>()),)).count(),
+        175,
+        "Optional view matches archetypes without the component"
+    );
+}
+
+#[test]
+fn optional_locks_only_present_components() {
+    let world = populate();
+
+    let mut floats = world.lock((write::<f32>(),));
+    let _floats = match poll_once(&mut floats) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    // Archetype with `f32` is locked.
+    let mut blocked = world.lock((read::<u32>(), Some(read::<f32>())));
+    assert!(poll_once(&mut blocked).is_pending());
+
+    // Archetypes without `f32` have nothing to lock.
+    let mut free = world.lock((Some(write::<f32>()), without::<f32>(), with::<Frozen>()));
+    let mut access = match poll_once(&mut free) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Optional component must not be locked"),
+    };
+
+    let count = access
+        .query_with((Some(write::<f32>()), without::<f32>(), with::<Frozen>()))
+        .inspect(|(float, (), ())| assert!(float.is_none()))
+        .count();
+    assert_eq!(count, 50);
+}
+
+#[derive(Debug, PartialEq)]
+struct Sprite(u32);
+
+#[derive(Debug, PartialEq)]
+struct Mesh(u32);
+
+fn populate_renderables() -> World {
+    let mut world = World::new();
+    for i in 0..10u32 {
+        world.spawn((i, Sprite(i)));
+    }
+    for i in 10..30u32 {
+        world.spawn((i, Mesh(i)));
+    }
+    for i in 30..60u32 {
+        world.spawn((i, Sprite(i), Mesh(i)));
+    }
+    for i in 60..100u32 {
+        world.spawn((i,));
+    }
+    world
+}
+
+#[test]
+fn or_filter() {
+    let mut world = populate_renderables();
+
+    let mut counts = [0; 4];
+    for (value, (sprite, mesh)) in world.query::<(Read<u32>, Or<(With<Sprite>, With<Mesh>)>)>() {
+        match *value {
+            0..=9 => assert_eq!((sprite, mesh), (true, false)),
+            10..=29 => assert_eq!((sprite, mesh), (false, true)),
+            30..=59 => assert_eq!((sprite, mesh), (true, true)),
+            _ => panic!("Entity without `Sprite` and `Mesh` matched"),
+        }
+        counts[sprite as usize * 2 + mesh as usize] += 1;
+    }
+    assert_eq!(counts, [0, 20, 10, 30]);
+
+    assert_eq!(
+        world
+            .query::<(Read<u32>, Or<(Without<Sprite>, With<Mesh>)>)>()
+            .count(),
+        90
+    );
+    assert_eq!(
+        world
+            .query::<Or<((With<Sprite>, With<Mesh>), With<f32>)>>()
+            .count(),
+        30
+    );
+
+    type Wide = Or<(
+        With<f32>,
+        With<u64>,
+        With<i8>,
+        With<i16>,
+        With<i32>,
+        With<i64>,
+        With<u8>,
+        With<u16>,
+        With<Frozen>,
+        With<bool>,
+        With<char>,
+        With<Mesh>,
+    )>;
+    assert_eq!(world.query::<Wide>().count(), 50);
+}
+
+#[test]
+fn any_of_view() {
+    let mut world = populate_renderables();
+
+    let mut count = 0;
+    for (value, (sprite, mesh)) in world.query::<(Read<u32>, AnyOf<(Read<Sprite>, Write<Mesh>)>)>()
+    {
+        assert!(sprite.is_some() || mesh.is_some());
+        if let Some(sprite) = sprite {
+            assert_eq!(sprite.0, *value);
+        }
+        if let Some(mesh) = mesh {
+            assert_eq!(mesh.0, *value);
+            mesh.0 += 1000;
+        }
+        count += 1;
+    }
+    assert_eq!(count, 60);
+
+    let count = world
+        .query::<AnyOf<(Read<f32>, Read<u64>, Read<i8>, Read<Sprite>, Read<Mesh>)>>()
+        .filter(|(.., sprite, mesh)| sprite.is_some() && mesh.is_some())
+        .count();
+    assert_eq!(count, 30);
+
+    assert!(world
+        .query::<(Read<u32>, Read<Mesh>)>()
+        .all(|(value, mesh)| mesh.0 == *value + 1000));
+}
+
+#[test]
+fn any_of_locks_only_matching_branches() {
+    let world = populate_renderables();
+
+    let mut meshes = world.lock((write::<Mesh>(), without::<Sprite>()));
+    let _meshes = match poll_once(&mut meshes) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    // Entities with `Sprite` only do not need `Mesh` lock.
+    let mut sprites = world.lock(AnyOf((read::<Sprite>(), with::<u64>())));
+    assert!(poll_once(&mut sprites).is_ready());
+
+    // Entities with `Mesh` only are locked.
+    let mut blocked = world.lock(AnyOf((read::<Sprite>(), read::<Mesh>())));
+    assert!(poll_once(&mut blocked).is_pending());
+}
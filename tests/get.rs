@@ -0,0 +1,133 @@
+mod common;
+
+use {
+    alex::{read, write, Entities, GetError, Read, World, Write},
+    common::{poll_once, populate},
+    std::{any::TypeId, task::Poll},
+};
+
+#[test]
+fn get_entity() {
+    let mut world = World::new();
+    let entities = populate(&mut world, 300);
+
+    let dead = entities[0];
+    world.despawn(dead).unwrap();
+    world.maintain();
+
+    let mut lock = world.lock((write::<u32>(), Some(read::<f32>())));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    for (i, &entity) in entities.iter().enumerate().skip(1) {
+        let (value, found) = access.get::<(Write<u32>, Entities)>(entity).unwrap();
+        assert_eq!(*value, i as u32);
+        assert_eq!(found, entity);
+        *value += 1000;
+
+        match i % 3 {
+            0 => assert!(matches!(
+                access.get::<Read<f32>>(entity),
+                Err(GetError::MissingComponents)
+            )),
+            _ => assert_eq!(*access.get::<Read<f32>>(entity).unwrap(), i as f32),
+        }
+    }
+
+    assert!(matches!(
+        access.get::<Read<u32>>(dead),
+        Err(GetError::NoSuchEntity)
+    ));
+
+    // Only shared access to `f32` is locked.
+    assert!(matches!(
+        access.get::<Write<f32>>(entities[1]),
+        Err(GetError::NotLocked)
+    ));
+
+    // `u64` is not locked at all.
+    assert!(matches!(
+        access.get::<Read<u64>>(entities[2]),
+        Err(GetError::NotLocked)
+    ));
+
+    drop(access);
+    drop(lock);
+
+    for (i, &entity) in entities.iter().enumerate().skip(1) {
+        assert_eq!(
+            *world.get_ref::<u32>(entity).unwrap().unwrap(),
+            i as u32 + 1000
+        );
+    }
+}
+
+#[test]
+fn get_many_entities() {
+    let mut world = World::new();
+    let entities = populate(&mut world, 300);
+
+    let mut lock = world.lock((write::<u32>(),));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    let [a, b, c] = access
+        .get_many_mut::<Write<u32>, 3>([entities[0], entities[4], entities[299]])
+        .unwrap();
+    std::mem::swap(a, b);
+    *c += 1;
+
+    assert_eq!(*access.get::<Read<u32>>(entities[0]).unwrap(), 4);
+    assert_eq!(*access.get::<Read<u32>>(entities[4]).unwrap(), 0);
+    assert_eq!(*access.get::<Read<u32>>(entities[299]).unwrap(), 300);
+
+    assert!(matches!(
+        access.get_many_mut::<Write<u32>, 3>([entities[0], entities[1], entities[0]]),
+        Err(GetError::DuplicateEntity)
+    ));
+
+    assert!(matches!(
+        access.get_many_mut::<Write<u32>, 2>([entities[0], world.reserve()]),
+        Err(GetError::MissingComponents)
+    ));
+
+    let values = access
+        .get_many_mut_with((read::<u32>(), Entities), [entities[10], entities[20]])
+        .unwrap();
+    assert_eq!(values, [(&10, entities[10]), (&20, entities[20])]);
+}
+
+#[test]
+fn get_by_component_id() {
+    let mut world = World::new();
+
+    // Ids are assigned in order of first appearance, unrelated to column order.
+    let a = world.spawn((1u8,));
+    let b = world.spawn((2u64, 2u8));
+    let c = world.spawn((3u16, 3u64, 3u8, 3.0f32));
+    assert!(world.component_id(TypeId::of::<u16>()) > world.component_id(TypeId::of::<u8>()));
+
+    assert_eq!(world.get_ref::<u8>(a).unwrap(), Some(&1));
+    assert_eq!(world.get_ref::<u64>(a).unwrap(), None);
+    assert_eq!(world.get_ref::<u64>(b).unwrap(), Some(&2));
+    assert_eq!(world.get_ref::<u8>(b).unwrap(), Some(&2));
+    assert_eq!(world.get_ref::<f32>(c).unwrap(), Some(&3.0));
+    assert_eq!(world.get_ref::<u16>(c).unwrap(), Some(&3));
+
+    *world.get_mut::<u64>(c).unwrap().unwrap() += 10;
+    world.insert(b, (20u16,)).unwrap();
+    world.remove::<(u8,)>(c).unwrap();
+
+    let mut values: Vec<_> = world
+        .query::<(Read<u16>, Read<u64>)>()
+        .map(|(a, b)| (*a, *b))
+        .collect();
+    values.sort_unstable();
+    assert_eq!(values, [(3, 13), (20, 2)]);
+    assert_eq!(world.get_ref::<u8>(c).unwrap(), None);
+    assert_eq!(world.get_ref::<u8>(b).unwrap(), Some(&2));
+}
@@ -0,0 +1,128 @@
+use {
+    alex::{ComponentInfo, Entity, RemoveError, World},
+    core::any::TypeId,
+    std::cell::RefCell,
+};
+
+thread_local! {
+    static LOG: RefCell<Vec<(&'static str, u32)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(event: &'static str, value: u32) {
+    LOG.with(|log| log.borrow_mut().push((event, value)));
+}
+
+fn take_log() -> Vec<(&'static str, u32)> {
+    LOG.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+struct Body(u32);
+
+struct Handle(u32);
+
+/// Despawns the entity when owner is despawned.
+struct Owned(Entity);
+
+fn body_info() -> ComponentInfo {
+    ComponentInfo::new::<Body>()
+        .on_add(|world, entity| {
+            let value = world.get_ref::<Body>(entity).unwrap().unwrap().0;
+            world.insert(entity, (Handle(value),)).unwrap();
+            log("add", value);
+        })
+        .on_insert(|world, entity| log("insert", world.get_ref::<Body>(entity).unwrap().unwrap().0))
+        .on_remove(|world, entity| {
+            log("remove", world.get_ref::<Body>(entity).unwrap().unwrap().0);
+            drop(world.remove::<(Handle,)>(entity));
+        })
+}
+
+#[test]
+fn add_and_insert_hooks() {
+    let mut world = World::new();
+    world.register_component(body_info());
+
+    let entity = world.spawn((Body(1), 0u32));
+    assert_eq!(take_log(), [("add", 1), ("insert", 1)]);
+    assert_eq!(world.get_ref::<Handle>(entity).unwrap().unwrap().0, 1);
+
+    // Overwriting runs only insert hook.
+    world.insert(entity, (Body(2),)).unwrap();
+    assert_eq!(take_log(), [("insert", 2)]);
+
+    let other = world.spawn((0u32,));
+    world.insert(other, (Body(3), 1.0f32)).unwrap();
+    assert_eq!(take_log(), [("add", 3), ("insert", 3)]);
+    assert!(world.get_ref::<Handle>(other).unwrap().is_some());
+
+    // Types without hooks are unaffected.
+    world.spawn((Handle(0),));
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn remove_hooks_run_before_removal() {
+    let mut world = World::new();
+    world.register_component(body_info());
+
+    let a = world.spawn((Body(1), 0u32));
+    let b = world.spawn((Body(2), 0u32));
+    take_log();
+
+    let (body,) = world.remove::<(Body,)>(a).unwrap();
+    assert_eq!(body.0, 1);
+    assert_eq!(take_log(), [("remove", 1)]);
+    assert!(world.get_ref::<Handle>(a).unwrap().is_none());
+
+    // Nothing runs if entity misses components.
+    assert!(matches!(
+        world.remove::<(Body, f32)>(b),
+        Err(RemoveError::MissingComponents)
+    ));
+    assert!(world.remove::<(Body,)>(a).is_err());
+    assert!(take_log().is_empty());
+
+    world.remove_dyn(b, &[TypeId::of::<Body>()]).unwrap();
+    assert_eq!(take_log(), [("remove", 2)]);
+}
+
+#[test]
+fn despawn_runs_remove_hooks_on_maintain() {
+    let mut world = World::new();
+    world.register_component(body_info());
+    world.register_component(ComponentInfo::new::<Owned>().on_remove(|world, entity| {
+        let owned = world.get_ref::<Owned>(entity).unwrap().unwrap().0;
+        world.despawn(owned).unwrap();
+    }));
+
+    let child = world.spawn((Body(2),));
+    let parent = world.spawn((Body(1), Owned(child)));
+    take_log();
+
+    world.despawn(parent).unwrap();
+    assert!(take_log().is_empty());
+
+    world.maintain();
+    let mut log = take_log();
+    log.sort_unstable();
+    assert_eq!(log, [("remove", 1), ("remove", 2)]);
+    assert!(world.get_ref::<Body>(parent).is_err());
+    assert!(world.get_ref::<Body>(child).is_err());
+
+    world.spawn((Body(3),));
+    world.spawn((Body(4),));
+    world.clear();
+    let mut log = take_log();
+    log.sort_unstable();
+    assert_eq!(
+        log,
+        [
+            ("add", 3),
+            ("add", 4),
+            ("insert", 3),
+            ("insert", 4),
+            ("remove", 3),
+            ("remove", 4)
+        ]
+    );
+}
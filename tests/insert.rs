@@ -0,0 +1,112 @@
+use {
+    alex::{NoSuchEntity, Read, World},
+    std::sync::Arc,
+};
+
+#[test]
+fn insert_moves_entity_to_new_archetype() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..100u32).map(|i| world.spawn((i,))).collect();
+
+    assert_eq!(world.insert(entities[10], (10.0f32,)).unwrap(), (None,));
+    assert_eq!(world.get_ref::<f32>(entities[10]).unwrap(), Some(&10.0));
+    assert_eq!(world.get_ref::<u32>(entities[10]).unwrap(), Some(&10));
+
+    // Entity moved into the hole keeps its components.
+    for (i, &entity) in entities.iter().enumerate() {
+        assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&(i as u32)));
+        assert_eq!(world.get_ref::<f32>(entity).unwrap().is_some(), i == 10);
+    }
+
+    assert_eq!(world.query::<Read<u32>>().count(), 100);
+    assert_eq!(world.query::<(Read<u32>, Read<f32>)>().count(), 1);
+}
+
+#[test]
+fn insert_replaces_present_components() {
+    let mut world = World::new();
+    let old = Arc::new(());
+    let new = Arc::new(());
+    let entity = world.spawn((1u32, old.clone()));
+
+    let (value, arc) = world.insert(entity, (2u32, new.clone())).unwrap();
+    assert_eq!(value, Some(1));
+    assert!(Arc::ptr_eq(&arc.unwrap(), &old));
+
+    assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&2));
+    assert!(Arc::ptr_eq(
+        world.get_ref::<Arc<()>>(entity).unwrap().unwrap(),
+        &new
+    ));
+    assert_eq!(world.query::<Read<u32>>().count(), 1);
+}
+
+#[test]
+fn insert_replaces_in_place() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..10u32).map(|i| world.spawn((i, i as f32))).collect();
+
+    assert_eq!(world.insert(entities[3], (30u32,)).unwrap(), (Some(3),));
+    assert_eq!(
+        world.insert(entities[5], (50.0f32, 50u32)).unwrap(),
+        (Some(5.0), Some(5))
+    );
+
+    // Entities keep their rows.
+    let values: Vec<u32> = world.query::<Read<u32>>().copied().collect();
+    assert_eq!(values, [0, 1, 2, 30, 4, 50, 6, 7, 8, 9]);
+    assert_eq!(world.get_ref::<f32>(entities[3]).unwrap(), Some(&3.0));
+    assert_eq!(world.get_ref::<f32>(entities[5]).unwrap(), Some(&50.0));
+}
+
+#[test]
+fn insert_partially_overlapping_bundle() {
+    let mut world = World::new();
+    let old = Arc::new(());
+    let other = world.spawn((0u32, Arc::new(())));
+    let entity = world.spawn((1u32, old.clone()));
+
+    // Present components are replaced and returned, missing are added.
+    assert_eq!(
+        world.insert(entity, (3u32, 1.5f32)).unwrap(),
+        (Some(1), None)
+    );
+    assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&3));
+    assert_eq!(world.get_ref::<f32>(entity).unwrap(), Some(&1.5));
+    assert_eq!(Arc::strong_count(&old), 2);
+
+    let (arc, c) = world.insert(entity, (Arc::new(()), 'x')).unwrap();
+    assert!(Arc::ptr_eq(arc.as_ref().unwrap(), &old));
+    assert_eq!(c, None);
+    drop(arc);
+    assert_eq!(Arc::strong_count(&old), 1);
+    assert_eq!(world.get_ref::<char>(entity).unwrap(), Some(&'x'));
+    assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&3));
+
+    assert_eq!(world.get_ref::<u32>(other).unwrap(), Some(&0));
+    assert!(world.get_ref::<f32>(other).unwrap().is_none());
+}
+
+#[test]
+fn insert_into_empty_entity() {
+    let mut world = World::new();
+    let entity = world.spawn(());
+    assert!(world.get_ref::<u32>(entity).unwrap().is_none());
+
+    assert_eq!(world.insert(entity, (5u32,)).unwrap(), (None,));
+    assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&5));
+    assert_eq!(world.insert(entity, (6u32,)).unwrap(), (Some(5),));
+    assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&6));
+}
+
+#[test]
+fn insert_into_reserved_and_despawned() {
+    let mut world = World::new();
+    let reserved = world.reserve();
+    assert_eq!(world.insert(reserved, (5u32,)).unwrap(), (None,));
+    assert_eq!(world.get_ref::<u32>(reserved).unwrap(), Some(&5));
+
+    world.despawn(reserved).unwrap();
+    world.maintain();
+    assert!(matches!(world.insert(reserved, (6u32,)), Err(NoSuchEntity)));
+}
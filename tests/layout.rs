@@ -0,0 +1,125 @@
+use alex::{Archetype, ComponentInfo, World, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(align(32))]
+struct Simd([f32; 8]);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(align(16))]
+struct Quat([f32; 4]);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Tag;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct AlignedTag([u64; 0]);
+
+fn is_aligned<T>(value: &T) -> bool {
+    (value as *const T as usize).is_multiple_of(core::mem::align_of::<T>())
+}
+
+fn check_mixed(mut world: World) {
+    let entities = (0..1000u32)
+        .map(|i| {
+            world.spawn((
+                i as u8,
+                Simd([i as f32; 8]),
+                u64::from(i),
+                Quat([i as f32; 4]),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    for (i, &entity) in entities.iter().enumerate() {
+        assert_eq!(*world.get_ref::<u8>(entity).unwrap().unwrap(), i as u8);
+
+        let simd = world.get_ref::<Simd>(entity).unwrap().unwrap();
+        assert!(is_aligned(simd));
+        assert_eq!(*simd, Simd([i as f32; 8]));
+
+        let long = world.get_ref::<u64>(entity).unwrap().unwrap();
+        assert!(is_aligned(long));
+        assert_eq!(*long, i as u64);
+
+        let quat = world.get_ref::<Quat>(entity).unwrap().unwrap();
+        assert!(is_aligned(quat));
+        assert_eq!(*quat, Quat([i as f32; 4]));
+    }
+}
+
+#[test]
+fn mixed_alignments() {
+    check_mixed(World::new());
+}
+
+#[test]
+fn cache_line_columns() {
+    check_mixed(World::with_column_align(64));
+}
+
+#[test]
+fn zero_sized_components() {
+    let mut world = World::new();
+
+    let entities = (0..1000u32)
+        .map(|i| world.spawn((Tag, AlignedTag([]), Simd([i as f32; 8]), i as u16)))
+        .collect::<Vec<_>>();
+
+    for (i, &entity) in entities.iter().enumerate() {
+        assert!(is_aligned(
+            world.get_ref::<AlignedTag>(entity).unwrap().unwrap()
+        ));
+
+        let simd = world.get_ref::<Simd>(entity).unwrap().unwrap();
+        assert!(is_aligned(simd));
+        assert_eq!(*simd, Simd([i as f32; 8]));
+
+        assert_eq!(world.get_ref::<Tag>(entity).unwrap(), Some(&Tag));
+        assert_eq!(*world.get_ref::<u16>(entity).unwrap().unwrap(), i as u16);
+    }
+}
+
+#[test]
+fn migration_keeps_alignment() {
+    let mut world = World::new();
+
+    let entities = (0..100u32)
+        .map(|i| world.spawn((i as u8,)))
+        .collect::<Vec<_>>();
+
+    for (i, &entity) in entities.iter().enumerate() {
+        world.insert(entity, (Simd([i as f32; 8]), Tag)).unwrap();
+    }
+
+    for (i, &entity) in entities.iter().enumerate() {
+        let simd = world.get_ref::<Simd>(entity).unwrap().unwrap();
+        assert!(is_aligned(simd));
+        assert_eq!(*simd, Simd([i as f32; 8]));
+        assert_eq!(*world.get_ref::<u8>(entity).unwrap().unwrap(), i as u8);
+    }
+}
+
+#[test]
+fn change_ticks_fit_in_chunk() {
+    let bytes = Archetype::new(vec![ComponentInfo::new::<u8>()].into_boxed_slice()).unwrap();
+    assert!(bytes.chunk_layout().size() <= 16384 + 64);
+
+    // Tags have no change ticks and do not affect layout.
+    let tagged = Archetype::new(
+        vec![
+            ComponentInfo::new::<u8>(),
+            ComponentInfo::new::<Tag>(),
+            ComponentInfo::new::<AlignedTag>(),
+        ]
+        .into_boxed_slice(),
+    )
+    .unwrap();
+    assert_eq!(tagged.chunk_capacity(), bytes.chunk_capacity());
+    assert_eq!(tagged.chunk_layout(), bytes.chunk_layout());
+
+    let mut world = World::new();
+    for i in 0..1000u32 {
+        world.spawn((i as u8, Tag));
+    }
+    assert_eq!(world.query::<(Write<u8>, Write<Tag>)>().count(), 1000);
+}
@@ -0,0 +1,221 @@
+use {
+    alex::{read, write, Read, World, Write},
+    std::{
+        future::Future,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering::SeqCst},
+            Arc,
+        },
+        task::{Context, Poll, Wake, Waker},
+        thread::{self, Thread},
+    },
+};
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future + Unpin>(mut future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut ctx = Context::from_waker(&waker);
+    loop {
+        match Pin::new(&mut future).poll(&mut ctx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+struct Counter(AtomicUsize);
+
+impl Wake for Counter {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, SeqCst);
+    }
+}
+
+fn poll_once<F: Future + Unpin>(future: &mut F, counter: &Arc<Counter>) -> Poll<F::Output> {
+    let waker = Waker::from(counter.clone());
+    let mut ctx = Context::from_waker(&waker);
+    Pin::new(future).poll(&mut ctx)
+}
+
+const THREADS: usize = 8;
+const ITERATIONS: usize = 64;
+const ENTITIES: u64 = 64;
+
+#[test]
+fn writers_exclude_readers() {
+    let mut world = World::new();
+    for _ in 0..ENTITIES {
+        world.spawn((0u64, 0u32));
+    }
+    let active = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let world = &world;
+            let active = &active;
+            scope.spawn(move || {
+                for _ in 0..ITERATIONS {
+                    if t % 2 == 0 {
+                        let mut access = block_on(world.lock((write::<u64>(),)));
+                        assert_eq!(active.swap(usize::MAX, SeqCst), 0);
+                        for value in access.query::<Write<u64>>() {
+                            *value += 1;
+                        }
+                        assert_eq!(active.swap(0, SeqCst), usize::MAX);
+                    } else {
+                        let mut access = block_on(world.lock((read::<u64>(),)));
+                        assert_ne!(active.fetch_add(1, SeqCst), usize::MAX);
+                        let mut values = access.query::<Read<u64>>();
+                        let first = *values.next().unwrap();
+                        assert!(values.all(|value| *value == first));
+                        active.fetch_sub(1, SeqCst);
+                    }
+                }
+            });
+        }
+    });
+
+    let expected = (THREADS / 2 * ITERATIONS) as u64;
+    assert_eq!(world.query::<Read<u64>>().count(), ENTITIES as usize);
+    assert!(world.query::<Read<u64>>().all(|value| *value == expected));
+}
+
+#[test]
+fn multiple_locks_make_progress() {
+    let mut world = World::new();
+    for _ in 0..ENTITIES {
+        world.spawn((0u64, 0u32));
+        world.spawn((0u64,));
+        world.spawn((0u32, 0u8));
+    }
+
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let world = &world;
+            scope.spawn(move || {
+                for _ in 0..ITERATIONS {
+                    match t % 4 {
+                        0 => {
+                            let mut access = block_on(world.lock((write::<u64>(), read::<u32>())));
+                            for (a, _) in access.query::<(Write<u64>, Read<u32>)>() {
+                                *a += 1;
+                            }
+                        }
+                        1 => {
+                            let mut access = block_on(world.lock((read::<u64>(), write::<u32>())));
+                            for (_, b) in access.query::<(Read<u64>, Write<u32>)>() {
+                                *b += 1;
+                            }
+                        }
+                        2 => {
+                            let mut access = block_on(world.lock((write::<u32>(),)));
+                            for b in access.query::<Write<u32>>() {
+                                *b += 1;
+                            }
+                        }
+                        _ => {
+                            let mut access = block_on(world.lock((read::<u8>(), read::<u64>())));
+                            assert_eq!(access.query::<Read<u64>>().count(), 0);
+                            let access = block_on(world.lock((write::<u64>(),)));
+                            drop(access);
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    let rounds = (THREADS / 4 * ITERATIONS) as u64;
+    for (a, b) in world.query::<(Read<u64>, Read<u32>)>() {
+        assert_eq!(*a, rounds);
+        assert_eq!(*b as u64, rounds * 2);
+    }
+    for (b, _) in world.query::<(Read<u32>, Read<u8>)>() {
+        assert_eq!(*b as u64, rounds);
+    }
+}
+
+#[test]
+fn waiters_are_not_duplicated() {
+    let mut world = World::new();
+    world.spawn((0u64,));
+
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+
+    let mut writer = world.lock((write::<u64>(),));
+    let guard = match poll_once(&mut writer, &counter) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    let mut reader = world.lock((read::<u64>(),));
+    for _ in 0..100 {
+        assert!(poll_once(&mut reader, &counter).is_pending());
+    }
+
+    drop(guard);
+    assert_eq!(counter.0.load(SeqCst), 1);
+    assert!(poll_once(&mut reader, &counter).is_ready());
+}
+
+#[test]
+fn dropped_waiters_are_removed() {
+    let mut world = World::new();
+    world.spawn((0u64,));
+
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+
+    let guard = block_on(world.lock((read::<u64>(),)));
+
+    for _ in 0..10 {
+        let mut writer = world.lock((write::<u64>(),));
+        assert!(poll_once(&mut writer, &counter).is_pending());
+    }
+
+    // Dropped writers do not block new readers.
+    let mut reader = world.lock((read::<u64>(),));
+    assert!(poll_once(&mut reader, &counter).is_ready());
+
+    drop(guard);
+    assert_eq!(counter.0.load(SeqCst), 0);
+
+    let mut writer = world.lock((write::<u64>(),));
+    assert!(poll_once(&mut writer, &counter).is_ready());
+}
+
+#[test]
+fn readers_do_not_overtake_writer() {
+    let mut world = World::new();
+    world.spawn((0u64,));
+
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+
+    let first = block_on(world.lock((read::<u64>(),)));
+
+    let mut writer = world.lock((write::<u64>(),));
+    assert!(poll_once(&mut writer, &counter).is_pending());
+
+    let mut reader = world.lock((read::<u64>(),));
+    assert!(poll_once(&mut reader, &counter).is_pending());
+
+    drop(first);
+    assert_eq!(counter.0.load(SeqCst), 1);
+    assert!(poll_once(&mut reader, &counter).is_pending());
+
+    let guard = match poll_once(&mut writer, &counter) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Writer must be admitted"),
+    };
+    assert!(poll_once(&mut reader, &counter).is_pending());
+
+    drop(guard);
+    assert!(poll_once(&mut reader, &counter).is_ready());
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use {
+    alex::{read, write, LockFuture, Read, World, Write},
+    common::poll_once,
+    std::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+/// Future that is pending on first poll.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            ctx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+fn populate() -> World {
+    let mut world = World::new();
+    for i in 0..10u32 {
+        world.spawn((i, i as u64));
+        world.spawn((i, i as f32));
+    }
+    world
+}
+
+#[test]
+fn locks_are_acquired_all_at_once() {
+    let world = populate();
+
+    let mut floats = world.lock((write::<u32>(), read::<f32>()));
+    let floats = match poll_once(&mut floats) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Locks are free"),
+    };
+
+    // Pending lock does not keep locks of available archetypes.
+    let mut all: LockFuture<'_, _> = world.lock((write::<u32>(),));
+    assert!(poll_once(&mut all).is_pending());
+
+    let mut plain = world.lock((read::<u32>(), read::<u64>()));
+    assert!(poll_once(&mut plain).is_ready());
+
+    drop(floats);
+    let mut access = match poll_once(&mut all) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Locks are released"),
+    };
+    assert_eq!(access.query::<Read<u32>>().count(), 20);
+}
+
+#[test]
+fn dropped_future_releases_nothing_held() {
+    let world = populate();
+
+    let mut first = world.lock((read::<u32>(),));
+    let first = match poll_once(&mut first) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Locks are free"),
+    };
+
+    let mut pending = world.lock((write::<u32>(), write::<f32>()));
+    assert!(poll_once(&mut pending).is_pending());
+    drop(pending);
+
+    // Cancelled waiter does not block other locks.
+    let mut floats = world.lock((write::<f32>(),));
+    assert!(poll_once(&mut floats).is_ready());
+
+    drop(first);
+    let mut writer = world.lock((write::<u32>(),));
+    assert!(poll_once(&mut writer).is_ready());
+}
+
+#[test]
+fn access_is_kept_across_await() {
+    let world = populate();
+
+    let mut task = Box::pin(async {
+        let mut access = world.lock((write::<u32>(),)).await;
+        YieldOnce(false).await;
+        for value in access.query::<Write<u32>>() {
+            *value += 1;
+        }
+        access.query::<Read<u32>>().copied().sum::<u32>()
+    });
+
+    fn assert_send<T: Send>(_: &T) {}
+    assert_send(&task);
+
+    assert!(poll_once(&mut task).is_pending());
+
+    // Lock is held while task is suspended.
+    let mut reader = world.lock((read::<u32>(),));
+    assert!(poll_once(&mut reader).is_pending());
+
+    assert_eq!(poll_once(&mut task), Poll::Ready(2 * 45 + 20));
+    drop(task);
+    assert!(poll_once(&mut reader).is_ready());
+}
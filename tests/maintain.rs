@@ -0,0 +1,75 @@
+use {
+    alex::{Entities, Read, World},
+    std::sync::Arc,
+};
+
+#[test]
+fn despawn_drops_on_maintain() {
+    let mut world = World::new();
+    let arc = Arc::new(());
+    let entity = world.spawn((1u32, arc.clone()));
+
+    // Despawning twice before maintenance drops components once.
+    world.despawn(entity).unwrap();
+    world.despawn(entity).unwrap();
+    assert_eq!(Arc::strong_count(&arc), 2);
+
+    world.maintain();
+    assert_eq!(Arc::strong_count(&arc), 1);
+    assert!(world.get_ref::<u32>(entity).is_err());
+    assert!(world.despawn(entity).is_err());
+    assert!(world.insert(entity, (2u32,)).is_err());
+    assert_eq!(world.query::<Read<u32>>().count(), 0);
+}
+
+#[test]
+fn maintain_compacts_storage() {
+    let mut world = World::new();
+    let arc = Arc::new(());
+    let entities: Vec<_> = (0..10000u32)
+        .map(|i| world.spawn((i, arc.clone())))
+        .collect();
+
+    for &entity in entities.iter().step_by(2) {
+        world.despawn(entity).unwrap();
+    }
+    world.maintain();
+    assert_eq!(Arc::strong_count(&arc), 5001);
+
+    // Entities moved into holes are still located correctly.
+    for (i, &entity) in entities.iter().enumerate().skip(1).step_by(2) {
+        assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&(i as u32)));
+    }
+    for (entity, value) in world.query::<(Entities, Read<u32>)>() {
+        assert_eq!(entities[*value as usize], entity);
+    }
+    assert_eq!(world.query::<Read<u32>>().count(), 5000);
+
+    // Freed rows are reused.
+    let entity = world.spawn((20000u32, arc.clone()));
+    assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&20000));
+    assert_eq!(Arc::strong_count(&arc), 5002);
+    assert_eq!(world.query::<Read<u32>>().count(), 5001);
+}
+
+#[test]
+fn maintain_materializes_reserved() {
+    let mut world = World::new();
+    let reserved: Vec<_> = (0..10).map(|_| world.reserve()).collect();
+
+    world.maintain();
+    for &entity in &reserved {
+        assert!(world.get_ref::<u32>(entity).unwrap().is_none());
+    }
+
+    world.insert(reserved[0], (1u32,)).unwrap();
+    world.despawn(reserved[1]).unwrap();
+    world.maintain();
+    assert!(world.get_ref::<u32>(reserved[1]).is_err());
+    assert_eq!(world.get_ref::<u32>(reserved[0]).unwrap(), Some(&1));
+
+    // Spawned entities do not collide with reserved ones.
+    let spawned = world.spawn((2u32,));
+    assert!(!reserved.contains(&spawned));
+    assert_eq!(world.get_ref::<u32>(reserved[0]).unwrap(), Some(&1));
+}
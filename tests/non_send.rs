@@ -0,0 +1,67 @@
+#![cfg(feature = "std")]
+
+use {
+    alex::{NonSend, World},
+    std::{rc::Rc, thread},
+};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn world_is_send_sync() {
+    assert_send_sync::<World>();
+    assert_send_sync::<NonSend<Rc<()>>>();
+}
+
+#[test]
+fn accessed_on_owner_thread() {
+    let mut world = World::new();
+    let rc = Rc::new(1u32);
+    let entity = world.spawn((NonSend::new(rc.clone()), 1u32));
+
+    let value = world.get_ref::<NonSend<Rc<u32>>>(entity).unwrap().unwrap();
+    assert_eq!(**value.get(), 1);
+    assert_eq!(Rc::strong_count(&rc), 2);
+
+    world.despawn(entity).unwrap();
+    world.maintain();
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn inaccessible_on_other_thread() {
+    let mut world = World::new();
+    let rc = Rc::new(1u32);
+    let entity = world.spawn((NonSend::new(rc.clone()),));
+
+    thread::scope(|scope| {
+        let world = &mut world;
+        scope.spawn(move || {
+            let value = world.get_ref::<NonSend<Rc<u32>>>(entity).unwrap().unwrap();
+            assert!(!value.is_owner());
+            assert!(value.try_get().is_none());
+        });
+    });
+
+    let value = world.get_ref::<NonSend<Rc<u32>>>(entity).unwrap().unwrap();
+    assert!(value.is_owner());
+    assert_eq!(value.try_get().map(|rc| **rc), Some(1));
+}
+
+#[test]
+#[should_panic]
+fn panics_on_other_thread() {
+    let value = NonSend::new(Rc::new(()));
+    let result = thread::scope(|scope| scope.spawn(|| drop(value.get().clone())).join());
+    std::panic::resume_unwind(result.unwrap_err());
+}
+
+#[test]
+fn leaked_when_dropped_on_other_thread() {
+    let mut world = World::new();
+    let rc = Rc::new(());
+    world.spawn((NonSend::new(rc.clone()),));
+
+    thread::spawn(move || drop(world)).join().unwrap();
+    assert_eq!(Rc::strong_count(&rc), 2);
+}
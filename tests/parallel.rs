@@ -0,0 +1,125 @@
+#![cfg(feature = "parallel")]
+
+mod common;
+
+use {
+    alex::{read, write, Entities, Entity, Read, With, World, Write},
+    common::{poll_once, populate_bodies, Frozen, Pos, Vel},
+    rayon::{prelude::*, ThreadPool, ThreadPoolBuilder},
+    std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        task::Poll,
+    },
+};
+
+fn pool() -> ThreadPool {
+    ThreadPoolBuilder::new().num_threads(4).build().unwrap()
+}
+
+#[test]
+fn par_for_each_visits_all_entities() {
+    let mut world = World::new();
+    populate_bodies(&mut world);
+
+    let visited = AtomicUsize::new(0);
+    pool().install(|| {
+        world.par_for_each::<(Write<Pos>, Read<Vel>), _>(|(pos, vel)| {
+            for (p, v) in pos.0.iter_mut().zip(&vel.0) {
+                *p += v;
+            }
+            visited.fetch_add(1, Ordering::Relaxed);
+        })
+    });
+    assert_eq!(visited.into_inner(), 10000);
+
+    for (pos, _) in world.query::<(Read<Pos>, Read<Vel>)>() {
+        assert_eq!(pos.0[1] - pos.0[0], 1.0);
+        assert_eq!(pos.0[2] - pos.0[0], 2.0);
+    }
+
+    let frozen = AtomicUsize::new(0);
+    pool().install(|| {
+        world.par_for_each::<(Read<Pos>, With<Frozen>), _>(|_| {
+            frozen.fetch_add(1, Ordering::Relaxed);
+        })
+    });
+    assert_eq!(frozen.into_inner(), 5000);
+}
+
+#[test]
+fn par_chunks_match_sequential_chunks() {
+    let mut world = World::new();
+    populate_bodies(&mut world);
+
+    let sequential: Vec<Vec<Entity>> = world
+        .query_chunks::<Entities>()
+        .map(|entities| entities.iter().collect())
+        .collect();
+
+    let parallel: Vec<Vec<Entity>> = pool().install(|| {
+        world
+            .par_iter_chunks::<Entities>()
+            .map(|entities| entities.iter().collect())
+            .collect()
+    });
+    assert_eq!(sequential, parallel);
+
+    let total: f32 = pool().install(|| {
+        world
+            .par_iter_chunks::<(Write<Pos>, Option<Read<Vel>>)>()
+            .map(|(pos, vel)| {
+                if let Some(vel) = vel {
+                    for (pos, vel) in pos.iter_mut().zip(vel) {
+                        pos.0 = vel.0;
+                    }
+                }
+                pos.iter().map(|pos| pos.0[0]).sum::<f32>()
+            })
+            .sum()
+    });
+    assert_eq!(total, 10000.0 + (0..100).sum::<i32>() as f32);
+}
+
+#[test]
+fn par_for_each_through_lock() {
+    let mut world = World::new();
+    populate_bodies(&mut world);
+
+    let mut lock = world.lock((write::<Pos>(), read::<Vel>()));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    pool().install(|| {
+        access.par_for_each::<(Write<Pos>, Read<Vel>), _>(|(pos, vel)| {
+            pos.0[0] = vel.0[2];
+        })
+    });
+
+    let total: usize = pool().install(|| {
+        access
+            .par_iter_chunks::<(Read<Pos>, Read<Vel>)>()
+            .map(|(pos, _)| {
+                assert!(pos.iter().all(|pos| pos.0[0] == 3.0));
+                pos.len()
+            })
+            .sum()
+    });
+    assert_eq!(total, 10000);
+}
+
+#[test]
+#[should_panic]
+fn par_for_each_requires_lock() {
+    let mut world = World::new();
+    populate_bodies(&mut world);
+
+    let mut lock = world.lock((read::<Pos>(),));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    access.par_for_each::<Write<Pos>, _>(|_| {});
+}
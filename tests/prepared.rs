@@ -0,0 +1,154 @@
+mod common;
+
+use {
+    alex::{read, Entities, PreparedQuery, Read, With, World, Write},
+    common::{populate, Noop},
+    std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+        future::Future,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll, Waker},
+    },
+};
+
+/// Counts allocations made by current thread.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+fn poll_with<F: Future + Unpin>(waker: &Waker, future: &mut F) -> Poll<F::Output> {
+    let mut ctx = Context::from_waker(waker);
+    Pin::new(future).poll(&mut ctx)
+}
+
+#[test]
+fn prepared_matches_new_archetypes() {
+    let mut world = World::new();
+    populate(&mut world, 1000);
+
+    let mut prepared = PreparedQuery::<(Write<u32>, Read<f32>)>::new();
+    assert_eq!(prepared.query(&mut world).count(), 666);
+
+    let generation = world.archetype_generation();
+    world.spawn((1u32, 1.0f32, 'a'));
+    world.spawn((2u32, 'b'));
+    assert_eq!(world.archetype_generation(), generation + 2);
+
+    for (value, float) in prepared.query(&mut world) {
+        *value += *float as u32;
+    }
+    // Entities with `u64` span two chunks.
+    assert_eq!(prepared.query_chunks(&mut world).count(), 4);
+    assert_eq!(
+        prepared
+            .query_chunks(&mut world)
+            .map(|(values, _)| values.len())
+            .sum::<usize>(),
+        667
+    );
+
+    for (value, float) in world.query::<(Read<u32>, Read<f32>)>() {
+        assert_eq!(*value, *float as u32 * 2);
+    }
+
+    // Plan is rebuilt for another world.
+    let mut other = World::new();
+    other.spawn((5u32, 5.0f32));
+    let values: Vec<u32> = prepared.query(&mut other).map(|(v, _)| *v).collect();
+    assert_eq!(values, [5]);
+}
+
+#[test]
+fn prepared_lock() {
+    let mut world = World::new();
+    populate(&mut world, 1000);
+
+    let waker = Waker::from(Arc::new(Noop));
+    let mut prepared = PreparedQuery::<(Write<u32>, Entities, With<u64>)>::new();
+
+    let mut lock = prepared.lock(&world);
+    let mut access = match poll_with(&waker, &mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    // Conflicting lock waits.
+    let mut other = world.lock((read::<u32>(),));
+    assert!(poll_with(&waker, &mut other).is_pending());
+
+    let mut count = 0;
+    for (value, _, ()) in access.query() {
+        *value += 1;
+        count += 1;
+    }
+    assert_eq!(count, 333);
+    assert_eq!(access.query_chunks().count(), 2);
+
+    drop(access);
+    drop(lock);
+
+    let mut access = match poll_with(&waker, &mut other) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is released"),
+    };
+    let sum: u64 = access.query::<Read<u32>>().map(|v| *v as u64).sum();
+    assert_eq!(sum, (0..1000).sum::<u64>() + 333);
+
+    // Prepared lock waits for the other lock.
+    let mut lock = prepared.lock(&world);
+    assert!(poll_with(&waker, &mut lock).is_pending());
+    drop(access);
+    drop(other);
+    assert!(poll_with(&waker, &mut lock).is_ready());
+}
+
+#[test]
+fn prepared_does_not_allocate() {
+    let mut world = World::new();
+    populate(&mut world, 1000);
+
+    let waker = Waker::from(Arc::new(Noop));
+    let mut prepared = PreparedQuery::<(Write<u32>, Read<f32>)>::new();
+
+    // Plan is built on first use.
+    prepared.query(&mut world).for_each(drop);
+
+    let before = allocations();
+    for _ in 0..10 {
+        for (value, float) in prepared.query(&mut world) {
+            *value += *float as u32;
+        }
+
+        let mut lock = prepared.lock(&world);
+        let mut access = match poll_with(&waker, &mut lock) {
+            Poll::Ready(access) => access,
+            Poll::Pending => panic!("Lock is free"),
+        };
+        for (value, _) in access.query() {
+            *value += 1;
+        }
+    }
+    assert_eq!(allocations(), before);
+}
@@ -0,0 +1,66 @@
+use {
+    alex::{Read, World, Write},
+    std::sync::Arc,
+};
+
+#[test]
+fn query_reads_and_writes() {
+    let mut world = World::new();
+    for i in 0..1000u32 {
+        match i % 2 {
+            0 => world.spawn((i, i as f32)),
+            _ => world.spawn((i, i as f32, 'x')),
+        };
+    }
+
+    for (value, float) in world.query::<(Read<u32>, Write<f32>)>() {
+        *float += *value as f32;
+    }
+    for (value, float) in world.query::<(Read<u32>, Read<f32>)>() {
+        assert_eq!(*float, *value as f32 * 2.0);
+    }
+
+    // Single views are not wrapped in tuples.
+    let sum: u64 = world.query::<Read<u32>>().map(|v| *v as u64).sum();
+    assert_eq!(sum, (0..1000).sum::<u64>());
+}
+
+#[test]
+fn query_matches_only_archetypes_with_components() {
+    let mut world = World::new();
+    assert_eq!(world.query::<Read<u32>>().count(), 0);
+
+    world.spawn((1u32,));
+    world.spawn((2u32, 2.0f32));
+    world.spawn((3.0f32,));
+    world.spawn((Arc::new(()),));
+
+    assert_eq!(world.query::<Read<u32>>().count(), 2);
+    assert_eq!(world.query::<Read<f32>>().count(), 2);
+    assert_eq!(world.query::<(Read<u32>, Read<f32>)>().count(), 1);
+    assert_eq!(world.query::<Read<u64>>().count(), 0);
+    assert_eq!(world.query::<()>().count(), 4);
+}
+
+#[test]
+fn query_sees_structural_changes() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..100u32).map(|i| world.spawn((i,))).collect();
+
+    for &entity in &entities[..10] {
+        world.insert(entity, (0.5f32,)).unwrap();
+    }
+    world.despawn(entities[0]).unwrap();
+    world.maintain();
+
+    let mut values: Vec<u32> = world
+        .query::<(Write<u32>, Read<f32>)>()
+        .map(|(value, _)| {
+            *value += 100;
+            *value
+        })
+        .collect();
+    values.sort_unstable();
+    assert_eq!(values, (101..110).collect::<Vec<_>>());
+    assert_eq!(world.query::<Read<u32>>().count(), 99);
+}
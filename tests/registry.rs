@@ -0,0 +1,101 @@
+use {
+    alex::{read, with, PreparedQuery, Read, With, World},
+    core::any::TypeId,
+};
+
+#[derive(Debug, PartialEq)]
+struct Tag<const N: usize>;
+
+macro_rules! insert_tags {
+    ($world:expr, $entity:expr, $($n:literal)*) => {
+        $( $world.insert($entity, (Tag::<$n>,)).unwrap(); )*
+    };
+}
+
+macro_rules! remove_tags {
+    ($world:expr, $entity:expr, $($n:literal)*) => {
+        $( assert_eq!($world.remove::<(Tag::<$n>,)>($entity).unwrap(), (Tag::<$n>,)); )*
+    };
+}
+
+#[test]
+fn component_ids_are_dense() {
+    let mut world = World::new();
+    assert_eq!(world.component_id(TypeId::of::<u32>()), None);
+
+    world.spawn((1u32, 2u64));
+    world.spawn((3u32, 4u8));
+
+    let mut ids = [
+        world.component_id(TypeId::of::<u32>()).unwrap().index(),
+        world.component_id(TypeId::of::<u64>()).unwrap().index(),
+        world.component_id(TypeId::of::<u8>()).unwrap().index(),
+    ];
+    ids.sort_unstable();
+    assert_eq!(ids, [0, 1, 2]);
+    assert_eq!(world.component_id(TypeId::of::<u16>()), None);
+
+    // Registries are per world.
+    let mut other = World::new();
+    other.spawn((5u8,));
+    assert_eq!(other.component_id(TypeId::of::<u8>()).unwrap().index(), 0);
+}
+
+#[test]
+fn many_archetypes_round_trip() {
+    let mut world = World::new();
+    let a = world.spawn((1u32,));
+    let b = world.spawn((2u32,));
+
+    // More components than fit in one signature word.
+    insert_tags!(world, a,
+        0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33
+        34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+        64 65 66 67 68 69
+    );
+    let generation = world.archetype_generation();
+    assert_eq!(generation, 71);
+
+    assert_eq!(world.query::<(Read<u32>, With<Tag<0>>)>().count(), 1);
+    assert_eq!(world.query::<(Read<u32>, With<Tag<69>>)>().count(), 1);
+
+    // Same path reuses existing archetypes.
+    insert_tags!(world, b,
+        0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33
+        34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+        64 65 66 67 68 69
+    );
+    assert_eq!(world.archetype_generation(), generation);
+    assert_eq!(world.query::<(Read<u32>, With<Tag<69>>)>().count(), 2);
+
+    // Removing in another order creates archetypes only for new subsets.
+    remove_tags!(world, a, 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19);
+    remove_tags!(world, b, 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19);
+    let generation = world.archetype_generation();
+
+    // Inserting tag already present keeps entity in place.
+    insert_tags!(world, a, 20 69);
+    assert_eq!(world.archetype_generation(), generation);
+
+    assert!(world.remove::<(Tag<0>,)>(a).is_err());
+    assert_eq!(world.query::<(Read<u32>, With<Tag<0>>)>().count(), 0);
+    assert_eq!(world.query::<(Read<u32>, With<Tag<20>>)>().count(), 2);
+    assert_eq!(*world.get_ref::<u32>(a).unwrap().unwrap(), 1);
+    assert_eq!(*world.get_ref::<u32>(b).unwrap().unwrap(), 2);
+}
+
+#[test]
+fn query_unregistered_components() {
+    let mut world = World::new();
+    world.spawn((1u32,));
+
+    assert_eq!(world.query::<(Read<u32>, Read<u64>)>().count(), 0);
+
+    let mut prepared = PreparedQuery::with_view((read::<u32>(), with::<u64>()));
+    assert_eq!(prepared.query(&mut world).count(), 0);
+
+    // Prepared query picks up components registered after it was updated.
+    world.spawn((2u32, 3u64));
+    let values: Vec<u32> = prepared.query(&mut world).map(|(v, ())| *v).collect();
+    assert_eq!(values, [2]);
+}
@@ -0,0 +1,73 @@
+use {
+    alex::{Read, RemoveError, World},
+    std::{any::TypeId, sync::Arc},
+};
+
+#[test]
+fn remove_returns_components() {
+    let mut world = World::new();
+    let arc = Arc::new(());
+    let entities: Vec<_> = (0..10u32)
+        .map(|i| world.spawn((i, i as f32, arc.clone())))
+        .collect();
+
+    let (value, float) = world.remove::<(u32, f32)>(entities[3]).unwrap();
+    assert_eq!((value, float), (3, 3.0));
+    assert!(world.get_ref::<u32>(entities[3]).unwrap().is_none());
+    assert!(world.get_ref::<Arc<()>>(entities[3]).unwrap().is_some());
+    assert_eq!(Arc::strong_count(&arc), 11);
+
+    for (i, &entity) in entities.iter().enumerate().filter(|&(i, _)| i != 3) {
+        assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&(i as u32)));
+    }
+    assert_eq!(world.query::<Read<u32>>().count(), 9);
+
+    // Removing last components leaves entity alive.
+    let (removed,) = world.remove::<(Arc<()>,)>(entities[3]).unwrap();
+    drop(removed);
+    assert_eq!(Arc::strong_count(&arc), 10);
+    assert!(world.get_ref::<Arc<()>>(entities[3]).unwrap().is_none());
+}
+
+#[test]
+fn remove_missing_components() {
+    let mut world = World::new();
+    let entity = world.spawn((1u32, 1.0f32));
+
+    assert!(matches!(
+        world.remove::<(u32, u64)>(entity),
+        Err(RemoveError::MissingComponents)
+    ));
+    assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&1));
+    assert_eq!(world.get_ref::<f32>(entity).unwrap(), Some(&1.0));
+
+    world.despawn(entity).unwrap();
+    world.maintain();
+    assert!(matches!(
+        world.remove::<(u32,)>(entity),
+        Err(RemoveError::NoSuchEntity)
+    ));
+}
+
+#[test]
+fn remove_dyn_drops_components() {
+    let mut world = World::new();
+    let arc = Arc::new(());
+    let entity = world.spawn((1u32, arc.clone()));
+    let other = world.spawn((2u32, arc.clone()));
+
+    world
+        .remove_dyn(entity, &[TypeId::of::<Arc<()>>()])
+        .unwrap();
+    assert_eq!(Arc::strong_count(&arc), 2);
+    assert_eq!(world.get_ref::<u32>(entity).unwrap(), Some(&1));
+    assert!(world.get_ref::<Arc<()>>(entity).unwrap().is_none());
+    assert!(world.get_ref::<Arc<()>>(other).unwrap().is_some());
+
+    assert!(matches!(
+        world.remove_dyn(entity, &[TypeId::of::<Arc<()>>()]),
+        Err(RemoveError::MissingComponents)
+    ));
+    world.remove_dyn(entity, &[TypeId::of::<u32>()]).unwrap();
+    assert!(world.get_ref::<u32>(entity).unwrap().is_none());
+}
@@ -0,0 +1,106 @@
+use {
+    alex::{Entity, RemovedComponents, World},
+    core::any::TypeId,
+};
+
+fn sorted(mut entities: Vec<Entity>) -> Vec<Entity> {
+    entities.sort_unstable_by_key(|e| e.index());
+    entities
+}
+
+#[test]
+fn removals_are_logged() {
+    let mut world = World::new();
+    let a = world.spawn((1u32, 1.0f32));
+    let b = world.spawn((2u32, 2.0f32));
+
+    let mut removed = RemovedComponents::<u32>::new();
+    assert_eq!(removed.read(&world).count(), 0);
+
+    world.remove::<(u32,)>(a).unwrap();
+    world.remove_dyn(b, &[TypeId::of::<u32>()]).unwrap();
+    assert_eq!(removed.read(&world).collect::<Vec<_>>(), [a, b]);
+
+    // Cursor advances past read entries.
+    assert_eq!(removed.read(&world).count(), 0);
+
+    // Failed removal is not logged.
+    assert!(world.remove::<(u32,)>(a).is_err());
+    assert_eq!(removed.read(&world).count(), 0);
+    assert_eq!(RemovedComponents::<f32>::new().read(&world).count(), 0);
+}
+
+#[test]
+fn despawns_are_logged_on_maintain() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..10u32).map(|i| world.spawn((i, i as f32))).collect();
+    let mut removed = RemovedComponents::<f32>::new();
+
+    world.despawn(entities[3]).unwrap();
+    world.despawn(entities[7]).unwrap();
+    assert_eq!(removed.read(&world).count(), 0);
+
+    world.maintain();
+    assert_eq!(
+        sorted(removed.read(&world).collect()),
+        [entities[3], entities[7]]
+    );
+
+    world.clear();
+    assert_eq!(removed.read(&world).count(), 8);
+}
+
+#[test]
+fn readers_are_independent() {
+    let mut world = World::new();
+    let a = world.spawn((1u32,));
+    let b = world.spawn((2u32,));
+
+    let mut first = RemovedComponents::<u32>::new();
+    let mut second = RemovedComponents::<u32>::default();
+
+    world.remove::<(u32,)>(a).unwrap();
+    assert_eq!(first.read(&world).collect::<Vec<_>>(), [a]);
+
+    world.remove::<(u32,)>(b).unwrap();
+    assert_eq!(first.read(&world).collect::<Vec<_>>(), [b]);
+    assert_eq!(second.read(&world).collect::<Vec<_>>(), [a, b]);
+
+    // Cursor is reset when used with another world.
+    let mut other = World::new();
+    let c = other.spawn((3u32,));
+    other.remove::<(u32,)>(c).unwrap();
+    assert_eq!(first.read(&other).collect::<Vec<_>>(), [c]);
+}
+
+#[test]
+fn entries_expire_after_retention() {
+    let mut world = World::new();
+    let a = world.spawn((1u32,));
+    let b = world.spawn((2u32,));
+
+    world.remove::<(u32,)>(a).unwrap();
+    world.maintain();
+    assert_eq!(RemovedComponents::<u32>::new().read(&world).count(), 1);
+
+    world.maintain();
+    assert_eq!(RemovedComponents::<u32>::new().read(&world).count(), 0);
+
+    world.set_removed_retention(3);
+    let mut removed = RemovedComponents::<u32>::new();
+    world.remove::<(u32,)>(b).unwrap();
+    world.maintain();
+    world.maintain();
+    world.maintain();
+    assert_eq!(removed.read(&world).collect::<Vec<_>>(), [b]);
+
+    // Entries expired before lagging reader gets to them are missed.
+    let c = world.spawn((3u32,));
+    let d = world.spawn((4u32,));
+    world.remove::<(u32,)>(c).unwrap();
+    for _ in 0..4 {
+        world.maintain();
+    }
+    world.remove::<(u32,)>(d).unwrap();
+    assert_eq!(removed.read(&world).collect::<Vec<_>>(), [d]);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use {
+    alex::{read, read_res, write, write_res, Read, World},
+    common::poll_once,
+    std::task::Poll,
+};
+
+#[derive(Debug, PartialEq)]
+struct Time(u32);
+
+#[derive(Debug, PartialEq)]
+struct Input(bool);
+
+#[test]
+fn insert_and_remove() {
+    let mut world = World::new();
+    assert!(world.resource::<Time>().is_none());
+
+    assert_eq!(world.insert_resource(Time(1)), None);
+    assert_eq!(world.insert_resource(Time(2)), Some(Time(1)));
+    assert_eq!(world.resource::<Time>(), Some(&Time(2)));
+
+    world.resource_mut::<Time>().unwrap().0 += 1;
+    assert_eq!(world.remove_resource::<Time>(), Some(Time(3)));
+    assert_eq!(world.remove_resource::<Time>(), None);
+}
+
+#[test]
+fn locked_with_components() {
+    let mut world = World::new();
+    world.insert_resource(Time(10));
+    world.insert_resource(Input(true));
+    for i in 0..5u32 {
+        world.spawn((i,));
+    }
+
+    let mut lock = world.lock((write::<u32>(), read_res::<Time>(), write_res::<Input>()));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Locks are free"),
+    };
+
+    let time = access.resource::<Time>().unwrap().0;
+    for (value,) in access.query_with((write::<u32>(),)) {
+        *value += time;
+    }
+    assert!(access.resource_mut::<Time>().is_none());
+    access.resource_mut::<Input>().unwrap().0 = false;
+    assert!(access.resource::<u64>().is_none());
+
+    drop(access);
+    drop(lock);
+    assert_eq!(world.resource::<Input>(), Some(&Input(false)));
+    let mut values: Vec<u32> = world.query::<(Read<u32>,)>().map(|(v,)| *v).collect();
+    values.sort_unstable();
+    assert_eq!(values, [10, 11, 12, 13, 14]);
+}
+
+#[test]
+fn resource_locks_conflict() {
+    let mut world = World::new();
+    world.insert_resource(Time(0));
+    world.spawn((0u32,));
+
+    let mut first = world.lock((read::<u32>(), write_res::<Time>()));
+    let first = match poll_once(&mut first) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Locks are free"),
+    };
+
+    // Components are shared, but resource is not.
+    let mut second = world.lock((read::<u32>(), read_res::<Time>()));
+    assert!(poll_once(&mut second).is_pending());
+
+    // Missing resources are not locked.
+    let mut third = world.lock((read_res::<Input>(),));
+    assert!(poll_once(&mut third).is_ready());
+
+    drop(first);
+    let second = match poll_once(&mut second) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Locks are released"),
+    };
+
+    // Shared and mutable access to one resource in the same request is merged.
+    let mut fourth = world.lock((read_res::<Time>(), write_res::<Time>()));
+    assert!(poll_once(&mut fourth).is_pending());
+    drop(second);
+    assert!(poll_once(&mut fourth).is_ready());
+}
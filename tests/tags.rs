@@ -0,0 +1,112 @@
+use {
+    alex::World,
+    core::sync::atomic::{AtomicUsize, Ordering::SeqCst},
+};
+
+#[derive(Debug, PartialEq)]
+struct Enemy;
+
+#[derive(Debug, PartialEq)]
+struct Frozen;
+
+#[derive(Debug, PartialEq)]
+#[repr(align(64))]
+struct Aligned;
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, PartialEq)]
+struct DropTag;
+
+impl Drop for DropTag {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+fn tags_only_archetype() {
+    let mut world = World::new();
+
+    let entities = (0..10000)
+        .map(|_| world.spawn((Enemy, Frozen, Aligned)))
+        .collect::<Vec<_>>();
+
+    for &entity in &entities {
+        assert_eq!(world.get_ref::<Enemy>(entity).unwrap(), Some(&Enemy));
+        assert_eq!(world.get_ref::<Frozen>(entity).unwrap(), Some(&Frozen));
+
+        let aligned = world.get_ref::<Aligned>(entity).unwrap().unwrap();
+        assert_eq!(aligned as *const Aligned as usize % 64, 0);
+        assert_eq!(world.get_ref::<u32>(entity).unwrap(), None);
+    }
+
+    for &entity in entities.iter().step_by(2) {
+        world.despawn(entity).unwrap();
+    }
+    world.maintain();
+
+    for (i, &entity) in entities.iter().enumerate() {
+        if i % 2 == 0 {
+            assert!(world.get_ref::<Enemy>(entity).is_err());
+        } else {
+            assert_eq!(world.get_ref::<Enemy>(entity).unwrap(), Some(&Enemy));
+        }
+    }
+}
+
+#[test]
+fn insert_and_remove_tags() {
+    let mut world = World::new();
+
+    let entities = (0..100u32)
+        .map(|i| world.spawn((i, Enemy)))
+        .collect::<Vec<_>>();
+
+    for &entity in entities.iter().step_by(3) {
+        assert_eq!(world.insert(entity, (Frozen,)).unwrap(), (None,));
+    }
+
+    for (i, &entity) in entities.iter().enumerate() {
+        let frozen = world.get_ref::<Frozen>(entity).unwrap();
+        assert_eq!(frozen.is_some(), i % 3 == 0);
+        assert_eq!(*world.get_ref::<u32>(entity).unwrap().unwrap(), i as u32);
+    }
+
+    for &entity in entities.iter().step_by(3) {
+        assert_eq!(
+            world.remove::<(Frozen, Enemy)>(entity).unwrap(),
+            (Frozen, Enemy)
+        );
+    }
+
+    for (i, &entity) in entities.iter().enumerate() {
+        assert_eq!(world.get_ref::<Frozen>(entity).unwrap(), None);
+        assert_eq!(
+            world.get_ref::<Enemy>(entity).unwrap().is_some(),
+            i % 3 != 0
+        );
+        assert_eq!(*world.get_ref::<u32>(entity).unwrap().unwrap(), i as u32);
+    }
+}
+
+#[test]
+fn tags_are_dropped() {
+    let mut world = World::new();
+
+    let entities = (0..10)
+        .map(|_| world.spawn((DropTag, Enemy)))
+        .collect::<Vec<_>>();
+
+    world
+        .remove_dyn(entities[0], &[core::any::TypeId::of::<DropTag>()])
+        .unwrap();
+    assert_eq!(DROPPED.load(SeqCst), 1);
+
+    world.despawn(entities[1]).unwrap();
+    world.maintain();
+    assert_eq!(DROPPED.load(SeqCst), 2);
+
+    drop(world);
+    assert_eq!(DROPPED.load(SeqCst), 10);
+}
@@ -0,0 +1,73 @@
+use {
+    alex::{Read, World},
+    std::sync::Arc,
+};
+
+/// Spawns entities spanning several chunks in few archetypes.
+fn populate(world: &mut World, arc: &Arc<()>) {
+    for i in 0..10000u32 {
+        match i % 3 {
+            0 => world.spawn((i, arc.clone())),
+            1 => world.spawn((i, arc.clone(), i as f32)),
+            _ => world.spawn((arc.clone(),)),
+        };
+    }
+}
+
+#[test]
+fn drop_world_drops_components() {
+    let arc = Arc::new(());
+    let mut world = World::new();
+    populate(&mut world, &arc);
+
+    // Removed and despawned entities are not dropped twice.
+    let entity = world.spawn((arc.clone(), 0u64));
+    drop(world.remove::<(Arc<()>,)>(entity).unwrap());
+    world.despawn(entity).unwrap();
+    assert_eq!(Arc::strong_count(&arc), 10001);
+
+    drop(world);
+    assert_eq!(Arc::strong_count(&arc), 1);
+}
+
+#[test]
+fn clear_drops_components() {
+    let arc = Arc::new(());
+    let mut world = World::new();
+    populate(&mut world, &arc);
+    let entity = world.spawn((0u32,));
+    let generation = world.archetype_generation();
+
+    world.clear();
+    assert_eq!(Arc::strong_count(&arc), 1);
+    assert!(world.get_ref::<u32>(entity).is_err());
+    assert_eq!(world.query::<Read<u32>>().count(), 0);
+
+    // Archetypes are kept for reuse.
+    populate(&mut world, &arc);
+    assert_eq!(world.archetype_generation(), generation);
+    assert_eq!(world.query::<Read<u32>>().count(), 6667);
+    assert_eq!(Arc::strong_count(&arc), 10001);
+
+    drop(world);
+    assert_eq!(Arc::strong_count(&arc), 1);
+}
+
+#[test]
+fn clear_discards_deferred_despawns() {
+    let arc = Arc::new(());
+    let mut world = World::new();
+    let entity = world.spawn((arc.clone(),));
+    world.despawn(entity).unwrap();
+    let reserved = world.reserve();
+
+    world.clear();
+    assert_eq!(Arc::strong_count(&arc), 1);
+    assert!(world.get_ref::<Arc<()>>(reserved).is_err());
+
+    world.maintain();
+    let entity = world.spawn((arc.clone(),));
+    world.maintain();
+    assert!(world.get_ref::<Arc<()>>(entity).unwrap().is_some());
+    assert_eq!(Arc::strong_count(&arc), 2);
+}
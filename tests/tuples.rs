@@ -0,0 +1,125 @@
+mod common;
+
+use {
+    alex::{read, write, And, Entities, Entity, Or, Read, With, Without, World, Write},
+    common::poll_once,
+    std::task::Poll,
+};
+
+type Twelve = (
+    Read<u8>,
+    Read<u16>,
+    Read<u32>,
+    Read<u64>,
+    Read<i8>,
+    Read<i16>,
+    Read<i32>,
+    Read<i64>,
+    Read<f32>,
+    Read<f64>,
+    Read<bool>,
+    Write<char>,
+);
+
+#[test]
+fn twelve_element_tuple() {
+    let mut world = World::new();
+    for i in 0..100u8 {
+        world.spawn((i, i as u16, i as u32, i as u64));
+        let entity = world.spawn((i, i as u16, i as u32, i as u64));
+        world
+            .insert(entity, (i as i8, i as i16, i as i32, i as i64))
+            .unwrap();
+        world
+            .insert(entity, (i as f32, i as f64, true, 'a'))
+            .unwrap();
+    }
+
+    let mut count = 0;
+    for (a, b, c, d, e, f, g, h, i, j, k, l) in world.query::<Twelve>() {
+        assert_eq!(*a as u16, *b);
+        assert_eq!(*c as u64, *d);
+        assert_eq!(*e as i16, *f);
+        assert_eq!(*g as i64, *h);
+        assert_eq!(*i as f64, *j);
+        assert!(*k);
+        *l = 'b';
+        count += 1;
+    }
+    assert_eq!(count, 100);
+
+    assert!(world.query::<Read<char>>().all(|c| *c == 'b'));
+    assert_eq!(world.query::<(Read<u8>, Read<u8>)>().count(), 200);
+}
+
+#[test]
+fn nested_tuples() {
+    let mut world = World::new();
+    for i in 0..100u32 {
+        match i % 3 {
+            0 => world.spawn((i,)),
+            1 => world.spawn((i, i as u64)),
+            _ => world.spawn((i, i as u64, i as f32)),
+        };
+    }
+
+    let mut entities: Vec<Entity> = Vec::new();
+    for ((value, (entity,)), (wide, ())) in
+        world.query::<((Write<u32>, (Entities,)), (Read<u64>, ()))>()
+    {
+        assert_eq!(*value as u64, *wide);
+        *value += 1;
+        entities.push(entity);
+    }
+    assert_eq!(entities.len(), 66);
+    for &entity in &entities {
+        assert!(world.get_ref::<u64>(entity).unwrap().is_some());
+    }
+
+    let mut count = 0;
+    for ((value, wide), float) in world.query::<(And<(Read<u32>, Read<u64>)>, Read<f32>)>() {
+        assert_eq!(*value as u64, *wide + 1);
+        assert_eq!(*float as u64, *wide);
+        count += 1;
+    }
+    assert_eq!(count, 33);
+
+    // Entities with `u64` and `f32` or without `u64` at all.
+    let count = world
+        .query::<Or<(And<(With<u64>, With<f32>)>, Without<u64>)>>()
+        .count();
+    assert_eq!(count, 67);
+}
+
+#[test]
+#[should_panic(expected = "Conflicting accesses")]
+fn conflicting_tuple() {
+    let mut world = World::new();
+    world.spawn((1u32,));
+    world.query::<(Read<u32>, Write<u32>)>().for_each(drop);
+}
+
+#[test]
+#[should_panic(expected = "Conflicting accesses")]
+fn conflicting_nested_tuple() {
+    let mut world = World::new();
+    world.spawn((1u32, 1.0f32));
+    world
+        .query_chunks::<((Write<u32>, Read<f32>), (Option<Write<u32>>,))>()
+        .for_each(drop);
+}
+
+#[test]
+#[should_panic(expected = "Conflicting accesses")]
+fn conflicting_get() {
+    let mut world = World::new();
+    let entity = world.spawn((1u32,));
+
+    let mut lock = world.lock((write::<u32>(), read::<u32>()));
+    let mut access = match poll_once(&mut lock) {
+        Poll::Ready(access) => access,
+        Poll::Pending => panic!("Lock is free"),
+    };
+
+    let _ = access.get::<(Write<u32>, Write<u32>)>(entity);
+}